@@ -0,0 +1,246 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use crate::{RenderSrcset, RenderUrl};
+use alloc::{borrow::Cow, vec, vec::Vec};
+use core::slice;
+use laby_common::{
+    internal::{is_valid_attribute_name, url_attribute_kind, Buffer, UrlKind},
+    Render,
+};
+
+/// Wraps an [`IntoIterator`] of `(name, value)` pairs, rendering them as attributes.
+///
+/// Each pair is rendered as ` name="value"`. Names that are not
+/// [valid](laby_common::internal::is_valid_attribute_name) are skipped along with their values,
+/// and values are escaped as usual. Values of URL attributes such as `href` are sanitized like
+/// [`RenderUrl`].
+///
+/// Element macros wrap spread attributes (e.g. `div!(..attrs)`) in this type automatically, so
+/// there is usually no need to construct this type manually. See [`Attributes`] for an example.
+#[derive(Debug, Clone)]
+pub struct RenderAttributes<I>(
+    /// The attributes to render.
+    pub I,
+);
+
+impl<I, N, V> Render for RenderAttributes<I>
+where
+    I: IntoIterator<Item = (N, V)>,
+    N: AsRef<str>,
+    V: Render,
+{
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        for (name, value) in self.0 {
+            let name = name.as_ref();
+
+            if !is_valid_attribute_name(name) {
+                continue;
+            }
+
+            buf.push(' ');
+            buf.push_str(name);
+            buf.push_str("=\"");
+
+            match url_attribute_kind(name) {
+                Some(UrlKind::Url) => RenderUrl(value).render(buf),
+                Some(UrlKind::Srcset) => RenderSrcset(value).render(buf),
+                None => value.render(buf),
+            }
+
+            buf.push('"');
+        }
+    }
+}
+
+impl<'a, I, N, V> Render for &'a RenderAttributes<I>
+where
+    &'a I: IntoIterator<Item = (N, V)>,
+    N: AsRef<str>,
+    V: Render,
+{
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        RenderAttributes(&self.0).render(buf)
+    }
+}
+
+/// Collection of attributes that can be spread into an element.
+///
+/// Attributes are rendered in insertion order. Inserting an attribute that already exists
+/// replaces its value in place.
+///
+/// Any [`IntoIterator`] of `(name, value)` pairs can be spread into an element using the `..`
+/// syntax, but this type is convenient when attributes need to be built up, inspected or
+/// forwarded by wrapper components. Spread attributes can be mixed freely with regular
+/// attributes, and are rendered in the order they are written.
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let mut attrs = Attributes::new();
+/// attrs.insert("aria-label", "close");
+/// attrs.insert("data-id", "5");
+///
+/// let n = button!(class = "btn", ..attrs, "×");
+///
+/// assert_eq!(
+///     render!(n),
+///     "<button class=\"btn\" aria-label=\"close\" data-id=\"5\">×</button>"
+/// );
+/// ```
+///
+/// Iterators of pairs can be spread directly. Invalid attribute names are skipped.
+///
+/// ```
+/// # use laby::*;
+/// let attrs = [("data-a", "1"), ("onclick=\"bad()\"", "2"), ("data-b", "<3")];
+/// let n = div!(..attrs);
+///
+/// assert_eq!(render!(n), "<div data-a=\"1\" data-b=\"&lt;3\"></div>");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attributes<'a> {
+    attrs: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+}
+
+impl<'a> Attributes<'a> {
+    /// Creates an empty collection.
+    #[inline]
+    pub fn new() -> Self {
+        Self { attrs: vec![] }
+    }
+
+    /// Returns the number of attributes in this collection.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.attrs.len()
+    }
+
+    /// Returns `true` if this collection contains no attributes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+
+    /// Returns the value of the attribute with the given name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_ref())
+    }
+
+    /// Inserts an attribute, replacing the value of an existing attribute with the same name.
+    pub fn insert(&mut self, name: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) {
+        let name = name.into();
+        let value = value.into();
+
+        match self.attrs.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => self.attrs.push((name, value)),
+        }
+    }
+
+    /// Inserts an attribute and returns this collection, for chaining.
+    #[inline]
+    pub fn with(mut self, name: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
+        self.insert(name, value);
+        self
+    }
+
+    /// Removes the attribute with the given name, returning its value.
+    pub fn remove(&mut self, name: &str) -> Option<Cow<'a, str>> {
+        let index = self.attrs.iter().position(|(n, _)| n == name)?;
+        Some(self.attrs.remove(index).1)
+    }
+
+    /// Returns an iterator over the names and values of the attributes in this collection.
+    #[inline]
+    pub fn iter(&self) -> AttributesIter<'_, 'a> {
+        AttributesIter(self.attrs.iter())
+    }
+}
+
+impl<'a, N, V> FromIterator<(N, V)> for Attributes<'a>
+where
+    N: Into<Cow<'a, str>>,
+    V: Into<Cow<'a, str>>,
+{
+    fn from_iter<T: IntoIterator<Item = (N, V)>>(iter: T) -> Self {
+        let mut attrs = Self::new();
+        attrs.extend(iter);
+        attrs
+    }
+}
+
+impl<'a, N, V> Extend<(N, V)> for Attributes<'a>
+where
+    N: Into<Cow<'a, str>>,
+    V: Into<Cow<'a, str>>,
+{
+    fn extend<T: IntoIterator<Item = (N, V)>>(&mut self, iter: T) {
+        for (name, value) in iter {
+            self.insert(name, value);
+        }
+    }
+}
+
+impl<'a> IntoIterator for Attributes<'a> {
+    type Item = (Cow<'a, str>, Cow<'a, str>);
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.attrs.into_iter()
+    }
+}
+
+impl<'b, 'a> IntoIterator for &'b Attributes<'a> {
+    type Item = (&'b str, &'b str);
+    type IntoIter = AttributesIter<'b, 'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Render for Attributes<'_> {
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        RenderAttributes(self).render(buf)
+    }
+}
+
+impl Render for &Attributes<'_> {
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        RenderAttributes(self).render(buf)
+    }
+}
+
+/// Iterator over the attributes in [`Attributes`].
+#[derive(Debug, Clone)]
+pub struct AttributesIter<'b, 'a>(slice::Iter<'b, (Cow<'a, str>, Cow<'a, str>)>);
+
+impl<'b> Iterator for AttributesIter<'b, '_> {
+    type Item = (&'b str, &'b str);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(n, v)| (n.as_ref(), v.as_ref()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
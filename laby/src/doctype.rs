@@ -13,6 +13,14 @@ use laby_common::{internal::Buffer, Render};
 /// This type can be used together with the [`render!`](crate::render) macro to generate a valid
 /// HTML document.
 ///
+/// XHTML documents, which are required by formats like EPUB, should be rendered with the
+/// [`render_xhtml!`](crate::render_xhtml) macro using [`DocType::XHTML5`]. Other XML documents
+/// like Atom feeds can start with [`DocType::XML`] instead.
+///
+/// Declarations of legacy document types are also available for pipelines that still require
+/// them. These are rendered exactly as specified, including the public and system identifiers.
+/// Other document types can be declared using [`CustomDocType`].
+///
 /// # Example
 ///
 /// ```
@@ -27,9 +35,38 @@ use laby_common::{internal::Buffer, Render};
 ///
 /// assert_eq!(n, "<!DOCTYPE html><html><head><title>laby</title></head><body></body></html>");
 /// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum DocType {
     /// Declaration for an HTML5 document.
     HTML5,
+
+    /// Declaration for an XHTML5 document, which is the [XML declaration](DocType::XML) followed
+    /// by the HTML5 declaration.
+    XHTML5,
+
+    /// Declaration for an HTML 4.01 Strict document.
+    HTML401Strict,
+
+    /// Declaration for an HTML 4.01 Transitional document.
+    HTML401Transitional,
+
+    /// Declaration for an XHTML 1.0 Strict document.
+    XHTML10Strict,
+
+    /// Declaration for an XHTML 1.0 Transitional document.
+    XHTML10Transitional,
+
+    /// Declaration for an XHTML 1.1 document.
+    XHTML11,
+
+    /// Declaration for an SVG 1.1 document.
+    SVG11,
+
+    /// XML declaration for an XML document encoded in UTF-8.
+    ///
+    /// Use [`XmlDeclaration`] to declare a different encoding or a standalone document.
+    XML,
 }
 
 impl Render for DocType {
@@ -38,6 +75,210 @@ impl Render for DocType {
             DocType::HTML5 => {
                 buf.push_str("<!DOCTYPE html>");
             }
+
+            DocType::XHTML5 => {
+                DocType::XML.render(buf);
+                DocType::HTML5.render(buf);
+            }
+
+            DocType::HTML401Strict => {
+                buf.push_str(concat!(
+                    r#"<!DOCTYPE HTML PUBLIC "-//W3C//DTD HTML 4.01//EN" "#,
+                    r#""http://www.w3.org/TR/html4/strict.dtd">"#
+                ));
+            }
+
+            DocType::HTML401Transitional => {
+                buf.push_str(concat!(
+                    r#"<!DOCTYPE HTML PUBLIC "-//W3C//DTD HTML 4.01 Transitional//EN" "#,
+                    r#""http://www.w3.org/TR/html4/loose.dtd">"#
+                ));
+            }
+
+            DocType::XHTML10Strict => {
+                buf.push_str(concat!(
+                    r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "#,
+                    r#""http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd">"#
+                ));
+            }
+
+            DocType::XHTML10Transitional => {
+                buf.push_str(concat!(
+                    r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Transitional//EN" "#,
+                    r#""http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd">"#
+                ));
+            }
+
+            DocType::XHTML11 => {
+                buf.push_str(concat!(
+                    r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.1//EN" "#,
+                    r#""http://www.w3.org/TR/xhtml11/DTD/xhtml11.dtd">"#
+                ));
+            }
+
+            DocType::SVG11 => {
+                buf.push_str(concat!(
+                    r#"<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "#,
+                    r#""http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd">"#
+                ));
+            }
+
+            DocType::XML => {
+                buf.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+            }
+        }
+    }
+}
+
+impl Render for &DocType {
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        (*self).render(buf)
+    }
+}
+
+/// Renders an XML declaration with the given encoding and standalone declaration, which are
+/// omitted if they are `None`.
+///
+/// Characters that are not allowed in the name of an encoding are removed, so the encoding cannot
+/// terminate the declaration.
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let n = render!(XmlDeclaration {
+///     encoding: Some("ISO-8859-1"),
+///     standalone: Some(true),
+/// });
+///
+/// assert_eq!(n, r#"<?xml version="1.0" encoding="ISO-8859-1" standalone="yes"?>"#);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct XmlDeclaration<'a> {
+    /// Name of the character encoding of the document.
+    pub encoding: Option<&'a str>,
+    /// Whether the document has no external markup declarations that affect its content.
+    pub standalone: Option<bool>,
+}
+
+impl Render for XmlDeclaration<'_> {
+    fn render(self, buf: &mut Buffer) {
+        buf.push_str(r#"<?xml version="1.0""#);
+
+        if let Some(encoding) = self.encoding {
+            buf.push_str(r#" encoding=""#);
+            push_filtered(encoding, buf, |c| {
+                c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')
+            });
+            buf.push('"');
+        }
+
+        match self.standalone {
+            Some(true) => buf.push_str(r#" standalone="yes""#),
+            Some(false) => buf.push_str(r#" standalone="no""#),
+            None => {}
+        }
+
+        buf.push_str("?>");
+    }
+}
+
+impl Render for &XmlDeclaration<'_> {
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        (*self).render(buf)
+    }
+}
+
+/// Renders a declaration for a document type with the given name and optional public and system
+/// identifiers.
+///
+/// If only a system identifier is given, it is declared using the `SYSTEM` keyword.
+///
+/// Document type declarations cannot contain character references, so the values are not escaped.
+/// Instead, characters that are not allowed in them are removed, so they cannot terminate the
+/// identifiers or the declaration. The name can only contain alphanumeric and non-ASCII characters
+/// and `.-_:`. The public identifier can only contain the characters allowed by the [XML
+/// specification][1]. The system identifier cannot contain `"` or `>`.
+///
+/// [1]: https://www.w3.org/TR/xml/#NT-PubidChar
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let n = render!(CustomDocType {
+///     name: "html",
+///     public: Some("-//W3C//DTD XHTML 1.0 Frameset//EN"),
+///     system: Some("http://www.w3.org/TR/xhtml1/DTD/xhtml1-frameset.dtd"),
+/// });
+///
+/// assert_eq!(
+///     n,
+///     "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Frameset//EN\" \
+///     \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-frameset.dtd\">"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CustomDocType<'a> {
+    /// Name of the root element of the document.
+    pub name: &'a str,
+    /// Public identifier of the document type.
+    pub public: Option<&'a str>,
+    /// System identifier of the document type, which is usually the URL of its DTD.
+    pub system: Option<&'a str>,
+}
+
+impl Render for CustomDocType<'_> {
+    fn render(self, buf: &mut Buffer) {
+        let system = |system: &str, buf: &mut Buffer| {
+            buf.push('"');
+            push_filtered(system, buf, |c| !c.is_control() && !matches!(c, '"' | '>'));
+            buf.push('"');
+        };
+
+        buf.push_str("<!DOCTYPE ");
+        push_filtered(self.name, buf, |c| {
+            c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | ':')
+        });
+
+        match (self.public, self.system) {
+            (Some(public), value) => {
+                buf.push_str(r#" PUBLIC ""#);
+                push_filtered(public, buf, |c| {
+                    c.is_ascii_alphanumeric() || " \r\n-'()+,./:=?;!*#@$_%".contains(c)
+                });
+                buf.push('"');
+
+                if let Some(value) = value {
+                    buf.push(' ');
+                    system(value, buf);
+                }
+            }
+
+            (None, Some(value)) => {
+                buf.push_str(" SYSTEM ");
+                system(value, buf);
+            }
+
+            (None, None) => {}
         }
+
+        buf.push('>');
+    }
+}
+
+impl Render for &CustomDocType<'_> {
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        (*self).render(buf)
+    }
+}
+
+/// Writes the characters of `value` for which `valid` returns `true`.
+fn push_filtered(value: &str, buf: &mut Buffer, valid: impl Fn(char) -> bool) {
+    for c in value.chars().filter(|&c| valid(c)) {
+        buf.push(c);
     }
 }
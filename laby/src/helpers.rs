@@ -6,8 +6,12 @@
 //
 //   https://opensource.org/licenses/MIT
 //
+use alloc::string::String;
 use core::fmt::Display;
-use laby_common::{internal::Buffer, Render};
+use laby_common::{
+    internal::{escape_srcset, escape_url, render_delimited, Buffer, EscapeContext},
+    Render,
+};
 
 /// Indicates that an attribute is an HTML boolean attribute.
 ///
@@ -44,13 +48,192 @@ macro_rules! bool {
     };
 }
 
+/// Indicates that an attribute is optional.
+///
+/// The argument to this macro must be an [`Option`] of a value that implements [`Render`]. If it
+/// evaluates to [`None`], the attribute is not rendered at all, including its name. Otherwise,
+/// the attribute is rendered as usual with the contained value.
+///
+/// Without this macro, [`Option`] values are rendered like any other value, so [`None`] renders
+/// an empty value (i.e. `name=""`).
+///
+/// Like [`bool!`](crate::bool), this is a special macro which is recognized internally by laby
+/// when used as an attribute value in expression position. If called outside a markup macro, or
+/// called in an invalid position, or the argument to this macro is not actually an [`Option`],
+/// compilation will fail.
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let title = Some("laby");
+/// let id: Option<&str> = None;
+/// let n = div!(title = opt!(title), id = opt!(id), "laby");
+///
+/// assert_eq!(render!(n), "<div title=\"laby\">laby</div>");
+/// ```
+#[macro_export]
+macro_rules! opt {
+    ($($x:tt)*) => {
+        ::core::compile_error!("invalid use of optional macro")
+    };
+}
+
+/// Disables validation of the HTML content model and attributes for the wrapped markup.
+///
+/// Element macros check statically nested elements against the [content model][1] of HTML at
+/// compile time, rejecting markup that is invalid or that browsers would parse differently from
+/// how it is written. For example, `p!(div!())` fails to compile because a `<div>` cannot be a
+/// child of a `<p>`, and browsers would close the `<p>` before the `<div>`. The following rules
+/// are checked:
+///
+/// - Elements like `<li>`, `<td>` and `<option>` must be children of the appropriate elements, and
+///   elements like `<ul>`, `<table>` and `<select>` can only contain those elements. Rows cannot
+///   be children of `<table>` directly; they must be wrapped in `<tbody>`.
+/// - Elements with phrasing content like `<p>`, `<span>` and headings cannot contain block-level
+///   elements like `<div>`.
+/// - Interactive elements like `<a>` and `<button>` cannot be nested in each other, and `<form>`
+///   elements cannot be nested in each other.
+///
+/// Attributes are validated as well, but violations are reported as warnings through the
+/// `deprecated` lint instead of errors. Attribute names written as identifiers are expected to be
+/// global attributes, attributes of the element, `data-*` or `aria-*` attributes, or event
+/// handlers like `onclick`. Unknown names are reported with the most similar known name as a
+/// suggestion. Literal values of enumerated attributes like `type` of `<input>` and `rel` of `<a>`
+/// are expected to be one of the allowed values. Attributes of custom elements and SVG or MathML
+/// elements are not validated.
+///
+/// Attribute names written as string literals are never validated either, which is the preferred
+/// way to opt out for a single attribute (e.g. `"enabled" = bool!(x)`) instead of wrapping the
+/// element in this macro or allowing the `deprecated` lint.
+///
+/// Only elements that are nested directly in other element macros are checked; elements rendered
+/// through variables or function calls are not. This macro can be used as a child to disable
+/// validation for the wrapped markup and all of its descendants, which is useful for intentional
+/// violations.
+///
+/// Like [`bool!`](crate::bool), this is a special macro which is recognized internally by laby
+/// when used as a child in expression position. If called outside a markup macro, or called in an
+/// invalid position, compilation will fail.
+///
+/// [1]: https://html.spec.whatwg.org/multipage/dom.html#content-models
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let n = ul!(unchecked!(div!(x_custom = "1", "laby")));
+///
+/// assert_eq!(render!(n), "<ul><div x-custom=\"1\">laby</div></ul>");
+/// ```
+///
+/// Without this macro, the above markup is rejected, in addition to the warning for `x_custom`.
+///
+/// ```compile_fail
+/// # use laby::*;
+/// let n = ul!(div!("laby"));
+/// ```
+#[macro_export]
+macro_rules! unchecked {
+    ($($x:tt)*) => {
+        ::core::compile_error!("invalid use of unchecked macro")
+    };
+}
+
+/// Marks a child as fallible in markup.
+///
+/// This is a marker macro recognized by element macros. The given value must implement the
+/// [`TryRender`] trait, such as a [`Result`] whose [`Ok`] value implements [`Render`]. Nodes with
+/// fallible children implement [`TryRender`] instead of [`Render`], and stop rendering at the
+/// first error returned by a child. Such nodes can be rendered using the
+/// [`try_render!`](crate::try_render) macro, or marked as fallible themselves when nested in other
+/// nodes.
+///
+/// Fallible children nested in the same markup macro invocation, such as `li!` in `ul!(li!(..))`,
+/// are flattened into the outermost node, so only values that are not constructed inline must be
+/// marked again.
+///
+/// It is an error to use this macro outside of markup.
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// fn lookup(id: u32) -> Result<&'static str, String> {
+///     match id {
+///         1 => Ok("laby"),
+///         _ => Err(format!("user {} not found", id)),
+///     }
+/// }
+///
+/// let n = ul!(li!(fallible!(lookup(1))));
+/// assert_eq!(try_render!(n), Ok("<ul><li>laby</li></ul>".into()));
+///
+/// let n = ul!(li!(fallible!(lookup(1))), li!(fallible!(lookup(2))));
+/// assert_eq!(try_render!(n), Err("user 2 not found".into()));
+/// ```
+///
+/// Nodes with fallible children cannot be rendered using [`render!`](crate::render).
+///
+/// ```compile_fail
+/// # use laby::*;
+/// let n = div!(fallible!(Ok::<_, ()>("laby")));
+/// let s = render!(n);
+/// ```
+#[macro_export]
+macro_rules! fallible {
+    ($($x:tt)*) => {
+        ::core::compile_error!("invalid use of fallible macro")
+    };
+}
+
+/// Marks a child as asynchronous in markup.
+///
+/// This is a marker macro recognized by element macros. The given value must implement the
+/// [`AsyncRender`] trait. Nodes with awaited children implement [`AsyncRender`] and
+/// [`SendAsyncRender`] instead of [`Render`], and wait for each awaited child to finish rendering
+/// before rendering the markup that follows it. Such nodes can be rendered using
+/// [`render_stream`], or marked as awaited themselves when nested in other nodes.
+///
+/// Awaited children nested in the same markup macro invocation, such as `li!` in `ul!(li!(..))`,
+/// are flattened into the outermost node, so only values that are not constructed inline must be
+/// marked again. Awaited children cannot be combined with [`fallible!`](crate::fallible) children
+/// in the same node.
+///
+/// It is an error to use this macro outside of markup.
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// struct User;
+///
+/// impl AsyncRender for User {
+///     async fn render_async(self, buffer: AsyncBuffer) {
+///         let name = async { "laby" }.await; // e.g. a database query
+///         buffer.render(name);
+///     }
+/// }
+///
+/// let n = ul!(li!(awaited!(User)), li!("static"));
+/// let mut stream = render_stream(n);
+/// # let _ = &mut stream;
+/// ```
+#[macro_export]
+macro_rules! awaited {
+    ($($x:tt)*) => {
+        ::core::compile_error!("invalid use of awaited macro")
+    };
+}
+
 #[allow(unused_imports)] // for docs
-use alloc::string::String;
+use laby_common::{render_stream, AsyncRender, SendAsyncRender, TryRender};
 
 /// Renders the given value and returns the result as a [`String`].
 ///
 /// This is a convenience macro that constructs a new [`Buffer`], renders the given expression into
-/// it, and returns the buffer as a [`String`].
+/// it, and returns the buffer as a [`String`]. The buffer is preallocated using the
+/// [size hint](Render::size_hint) of the value, or to 16384 bytes if the size hint is zero.
 ///
 /// The value must implement the [`Render`] trait.
 ///
@@ -62,7 +245,7 @@ use alloc::string::String;
 /// ```ignore
 /// // render!($expr)
 /// {
-///     let mut buf = Buffer::new();
+///     let mut buf = Buffer::with_size_hint($expr.size_hint());
 ///     $expr.render(&mut buf);
 ///     buf.into_string()
 /// }
@@ -136,11 +319,17 @@ use alloc::string::String;
 /// ```
 #[macro_export]
 macro_rules! render {
-    ($expr:expr) => {{
-        let mut buf = $crate::internal::Buffer::with_capacity(16384);
-        $crate::Render::render($expr, &mut buf);
-        buf.into_string()
-    }};
+    ($expr:expr) => {
+        match $expr {
+            value => {
+                let size = $crate::Render::size_hint(&value);
+                let mut buf = $crate::internal::Buffer::with_size_hint(size);
+
+                $crate::Render::render(value, &mut buf);
+                buf.into_string()
+            }
+        }
+    };
 
     ($($expr:expr),* $(,)?) => {{
         let frag = $crate::frag!($($expr),*);
@@ -148,6 +337,328 @@ macro_rules! render {
     }};
 }
 
+/// Renders the given value as XHTML and returns the result as a [`String`].
+///
+/// This macro behaves like [`render!`](crate::render), but the buffer is put into XHTML mode
+/// using [`Buffer::set_xhtml`](laby_common::internal::Buffer::set_xhtml), so that the output is
+/// well-formed XML as required by formats like EPUB. Void elements are closed in the start tag
+/// (`<br/>`), boolean attributes are given their name as the value (`disabled="disabled"`),
+/// `<html>` includes the XHTML namespace, and the contents of `<script>` and `<style>` are escaped
+/// like any other text. Characters that are not allowed in XML 1.0 are replaced when escaping,
+/// see [`escape_xml`](laby_common::internal::escape_xml).
+///
+/// The same node can be rendered as both HTML and XHTML.
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let n = || html!(body!(input!(disabled = bool!(true)), br!()));
+///
+/// assert_eq!(render!(n()), "<html><body><input disabled><br></body></html>");
+/// assert_eq!(
+///     render_xhtml!(DocType::XHTML5, n()),
+///     "<?xml version=\"1.0\" encoding=\"UTF-8\"?><!DOCTYPE html>\
+///     <html xmlns=\"http://www.w3.org/1999/xhtml\">\
+///     <body><input disabled=\"disabled\"/><br/></body></html>"
+/// );
+/// ```
+#[macro_export]
+macro_rules! render_xhtml {
+    ($expr:expr) => {
+        match $expr {
+            value => {
+                let size = $crate::Render::size_hint(&value);
+                let mut buf = $crate::internal::Buffer::with_size_hint(size);
+                buf.set_xhtml(true);
+
+                $crate::Render::render(value, &mut buf);
+                buf.into_string()
+            }
+        }
+    };
+
+    ($($expr:expr),* $(,)?) => {{
+        let frag = $crate::frag!($($expr),*);
+        $crate::render_xhtml!(frag)
+    }};
+}
+
+/// Renders the given fallible value and returns the result as a [`String`], or the first error
+/// that occurred while rendering.
+///
+/// This is the fallible counterpart of the [`render!`](crate::render) macro. The value must
+/// implement the [`TryRender`] trait, such as a [`Result`] or a node with children marked using
+/// the [`fallible!`](crate::fallible) macro. Partially rendered output is discarded on error.
+///
+/// If multiple values are given, they are rendered sequentially without delimiters until one of
+/// them fails. All values must have the same error type.
+///
+/// # Expansion
+///
+/// ```ignore
+/// // try_render!($expr)
+/// {
+///     let mut buf = Buffer::with_size_hint($expr.size_hint());
+///     $expr.try_render(&mut buf).map(|()| buf.into_string())
+/// }
+///
+/// // try_render!($expr*)
+/// {
+///     let mut buf = Buffer::new();
+///     let mut result = Ok(());
+///     $(if result.is_ok() { result = $expr.try_render(&mut buf); })*
+///     result.map(|()| buf.into_string())
+/// }
+/// ```
+///
+/// # Examples
+///
+/// Errors returned by fallible children are propagated to the caller.
+///
+/// ```
+/// # use laby::*;
+/// #[derive(Debug, PartialEq)]
+/// struct NotFound;
+///
+/// fn title(id: u32) -> Result<&'static str, NotFound> {
+///     if id == 1 { Ok("laby") } else { Err(NotFound) }
+/// }
+///
+/// fn page(id: u32) -> Result<String, NotFound> {
+///     try_render!(html!(head!(title!(fallible!(title(id)))), body!()))
+/// }
+///
+/// assert_eq!(page(1).unwrap(), "<html><head><title>laby</title></head><body></body></html>");
+/// assert_eq!(page(2), Err(NotFound));
+/// ```
+///
+/// This example renders multiple values, stopping at the first error.
+///
+/// ```
+/// # use laby::*;
+/// let ok: Result<_, &str> = Ok(div!());
+/// let err: Result<&str, &str> = Err("failed");
+///
+/// assert_eq!(try_render!(ok, Ok(p!())), Ok("<div></div><p></p>".into()));
+/// assert_eq!(try_render!(Ok(p!()), err), Err("failed"));
+/// ```
+#[macro_export]
+macro_rules! try_render {
+    ($expr:expr) => {
+        match $expr {
+            value => {
+                let size = $crate::TryRender::size_hint(&value);
+                let mut buf = $crate::internal::Buffer::with_size_hint(size);
+
+                match $crate::TryRender::try_render(value, &mut buf) {
+                    ::core::result::Result::Ok(()) => ::core::result::Result::Ok(buf.into_string()),
+                    ::core::result::Result::Err(error) => ::core::result::Result::Err(error),
+                }
+            }
+        }
+    };
+
+    ($($expr:expr),* $(,)?) => {{
+        let mut buf = $crate::internal::Buffer::with_size_hint(0);
+        let mut result = ::core::result::Result::Ok(());
+
+        $(
+            if result.is_ok() {
+                result = $crate::TryRender::try_render($expr, &mut buf);
+            }
+        )*
+
+        result.map(|()| buf.into_string())
+    }};
+}
+
+/// Renders the given value into an existing [`Buffer`], returning the rendered contents.
+///
+/// This macro behaves like [`render!`](crate::render), but reuses the memory of the given buffer
+/// instead of allocating a new [`String`] for every render. The buffer is cleared before
+/// rendering, and the macro evaluates to a `&str` borrowing the buffer.
+///
+/// The buffer must be the first argument. If multiple values are given after the buffer, they are
+/// wrapped using the [`frag!`][crate::frag] macro and rendered sequentially without delimiters.
+///
+/// To keep buffers between renders without passing them around, see
+/// [`BufferPool`](laby_common::internal::BufferPool), which also limits the memory held by large
+/// buffers.
+///
+/// # Expansion
+///
+/// ```ignore
+/// // render_into!($buffer, $expr*)
+/// {
+///     render_into(frag!($expr*), &mut $buffer)
+/// }
+/// ```
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let mut buf = internal::Buffer::new();
+///
+/// for name in ["a", "b"] {
+///     let s = render_into!(buf, p!("hello, ", name));
+///     assert_eq!(s, format!("<p>hello, {}</p>", name));
+/// }
+/// ```
+#[macro_export]
+macro_rules! render_into {
+    ($buffer:expr, $expr:expr $(,)?) => {
+        $crate::internal::render_into($expr, &mut $buffer)
+    };
+
+    ($buffer:expr, $($expr:expr),* $(,)?) => {{
+        let frag = $crate::frag!($($expr),*);
+        $crate::render_into!($buffer, frag)
+    }};
+}
+
+/// Renders the given value into a [`String`] with indentation for readability.
+///
+/// This macro behaves like [`render!`](crate::render), but places block-level elements on
+/// separate lines indented by their depth, which is useful for debugging emails and
+/// server-rendered pages. Whitespace-sensitive content such as text next to inline elements and
+/// the contents of `<pre>` and `<textarea>` is kept as-is. See
+/// [`prettify`](laby_common::internal::prettify) for the exact rules.
+///
+/// The value is rendered as usual and reformatted afterwards, so [`render!`](crate::render) is
+/// not affected in any way.
+///
+/// # Expansion
+///
+/// ```ignore
+/// // render_pretty!($expr*)
+/// {
+///     render_pretty(frag!($expr*))
+/// }
+/// ```
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let n = html!(
+///     head!(title!("laby")),
+///     body!(
+///         p!("hello, ", b!("world")),
+///         pre!("a\n  b"),
+///     ),
+/// );
+///
+/// assert_eq!(
+///     render_pretty!(n),
+///     "\
+/// <html>
+///   <head>
+///     <title>laby</title>
+///   </head>
+///   <body>
+///     <p>hello, <b>world</b></p>
+///     <pre>a
+///   b</pre>
+///   </body>
+/// </html>"
+/// );
+/// ```
+#[macro_export]
+macro_rules! render_pretty {
+    ($expr:expr $(,)?) => {
+        $crate::internal::render_pretty($expr)
+    };
+
+    ($($expr:expr),* $(,)?) => {{
+        let frag = $crate::frag!($($expr),*);
+        $crate::render_pretty!(frag)
+    }};
+}
+
+/// Renders the given value into a [`fmt::Write`](core::fmt::Write) in chunks.
+///
+/// This macro behaves like [`render!`](crate::render), but instead of returning the whole
+/// rendered result as a [`String`], it writes the result into the given writer in chunks of at
+/// most [`STREAM_CHUNK_SIZE`](laby_common::internal::STREAM_CHUNK_SIZE) bytes as rendering
+/// progresses. The memory used for rendering therefore stays bounded even for very large
+/// documents.
+///
+/// The writer must be the first argument. If multiple values are given after the writer, they are
+/// wrapped using the [`frag!`][crate::frag] macro and rendered sequentially without delimiters.
+///
+/// This macro evaluates to [`fmt::Result`](core::fmt::Result).
+///
+/// # Expansion
+///
+/// ```ignore
+/// // render_fmt!($writer, $expr*)
+/// {
+///     render_fmt(frag!($expr*), &mut $writer)
+/// }
+/// ```
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let mut s = String::new();
+///
+/// render_fmt!(s, DocType::HTML5, html!(body!("laby"))).unwrap();
+/// assert_eq!(s, "<!DOCTYPE html><html><body>laby</body></html>");
+/// ```
+#[macro_export]
+macro_rules! render_fmt {
+    ($writer:expr, $expr:expr $(,)?) => {
+        $crate::internal::render_fmt($expr, &mut $writer)
+    };
+
+    ($writer:expr, $($expr:expr),* $(,)?) => {{
+        let frag = $crate::frag!($($expr),*);
+        $crate::render_fmt!($writer, frag)
+    }};
+}
+
+/// Renders the given value into an [`io::Write`](std::io::Write) in chunks.
+///
+/// This macro is only available with the `std` feature. It behaves like
+/// [`render_fmt!`](crate::render_fmt), but writes the result into a byte-oriented writer such as
+/// a file or a socket.
+///
+/// This macro evaluates to [`io::Result<()>`](std::io::Result).
+///
+/// # Expansion
+///
+/// ```ignore
+/// // render_io!($writer, $expr*)
+/// {
+///     render_io(frag!($expr*), &mut $writer)
+/// }
+/// ```
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let mut v = Vec::new();
+///
+/// render_io!(v, DocType::HTML5, html!(body!("laby"))).unwrap();
+/// assert_eq!(v, b"<!DOCTYPE html><html><body>laby</body></html>");
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! render_io {
+    ($writer:expr, $expr:expr $(,)?) => {
+        $crate::internal::render_io($expr, &mut $writer)
+    };
+
+    ($writer:expr, $($expr:expr),* $(,)?) => {{
+        let frag = $crate::frag!($($expr),*);
+        $crate::render_io!($writer, frag)
+    }};
+}
+
 /// Wraps an [`Iterator`], making it implement [`Render`].
 ///
 /// This is a workaround for Rust's lack of impl specialization. When Rust gets specialization,
@@ -193,12 +704,28 @@ where
     }
 }
 
+impl<'a, I> Render for &'a RenderIterator<I>
+where
+    I: IntoIterator,
+    I::Item: Render,
+    &'a I: IntoIterator,
+    <&'a I as IntoIterator>::Item: Render,
+{
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        RenderIterator(&self.0).render(buf)
+    }
+}
+
 /// Wraps an [`Iterator`], making it implement [`Render`], with a delimiter between items.
 ///
 /// All items yielded by the iterator must implement [`Render`], which will be rendered
 /// sequentially, in the order they are yielded, with a string delimiter in-between. The delimiter
 /// is **not escaped**.
 ///
+/// Delimiters are only placed between items that render something, so items that render nothing
+/// (e.g. [`None`]) do not leave stray delimiters behind.
+///
 /// This renderer is lazy; the iterator wrapped by this type will only be iterated when
 /// [`render`][Render::render] is called on the value, not when the value is constructed.
 ///
@@ -223,17 +750,28 @@ where
 {
     #[inline]
     fn render(self, buf: &mut Buffer) {
-        let mut iter = self.0.into_iter();
         let del = self.1.as_ref();
+        let mut first = true;
 
-        if let Some(item) = iter.next() {
-            item.render(buf);
+        for item in self.0 {
+            if render_delimited(item, if first { "" } else { del }, buf) {
+                first = false;
+            }
         }
+    }
+}
 
-        for item in iter {
-            buf.push_str(del);
-            item.render(buf);
-        }
+impl<'a, I, S> Render for &'a RenderIteratorDelimited<I, S>
+where
+    I: IntoIterator,
+    I::Item: Render,
+    S: AsRef<str>,
+    &'a I: IntoIterator,
+    <&'a I as IntoIterator>::Item: Render,
+{
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        RenderIteratorDelimited(&self.0, self.1.as_ref()).render(buf)
     }
 }
 
@@ -252,6 +790,9 @@ where
 /// If you are rendering an iterator with the newline `"\n"` string as the delimiter, consider
 /// using the [`iter_lines!`](crate::iter_lines) macro instead.
 ///
+/// Collections like `Vec`, arrays and boxed slices, as well as tuples, implement [`Render`]
+/// themselves and can be rendered without this macro.
+///
 /// # Expansion
 ///
 /// ```ignore
@@ -396,6 +937,26 @@ where
     fn render(self, buf: &mut Buffer) {
         buf.push_str(self.0.as_ref());
     }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.0.as_ref().len()
+    }
+}
+
+impl<S> Render for &RenderRaw<S>
+where
+    S: AsRef<str>,
+{
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        buf.push_str(self.0.as_ref());
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.0.as_ref().len()
+    }
 }
 
 /// Wraps an [`AsRef<str>`] in [`RenderRaw`], rendering it without escaping.
@@ -472,6 +1033,16 @@ where
     }
 }
 
+impl<D> Render for &RenderDisplay<D>
+where
+    D: Display,
+{
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        format_args!("{}", self.0).render(buf)
+    }
+}
+
 /// Wraps a [`Display`] in [`RenderDisplay`], making it implement [`Render`].
 ///
 /// This is a convenience macro that wraps the given expression in [`RenderDisplay`].
@@ -520,3 +1091,206 @@ macro_rules! disp {
         $crate::RenderDisplay($expr)
     };
 }
+
+/// Wraps a [`Render`], sanitizing its rendered value as a URL.
+///
+/// The value is rendered into the buffer without escaping, and the result is replaced with its
+/// escaped form using [`escape_url`]. URLs with schemes other than those in
+/// [`SAFE_URL_SCHEMES`](crate::internal::SAFE_URL_SCHEMES) (e.g. `javascript:`) are replaced
+/// with [`INVALID_URL`](crate::internal::INVALID_URL).
+///
+/// Element macros wrap the values of known URL attributes such as `href` and `src` in this type
+/// automatically, unless the value is a literal, which is checked at compile time instead, or
+/// wrapped in [`raw!`](crate::raw).
+///
+/// Consider using the [`url!`](crate::url) macro instead of constructing this type manually.
+#[derive(Debug, Clone)]
+pub struct RenderUrl<R>(
+    /// The value to sanitize.
+    pub R,
+)
+where
+    R: Render;
+
+impl<R> From<R> for RenderUrl<R>
+where
+    R: Render,
+{
+    #[inline]
+    fn from(value: R) -> Self {
+        Self(value)
+    }
+}
+
+impl<R> Render for RenderUrl<R>
+where
+    R: Render,
+{
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        render_sanitized(self.0, buf, escape_url)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, R> Render for &'a RenderUrl<R>
+where
+    R: Render,
+    &'a R: Render,
+{
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        RenderUrl(&self.0).render(buf)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+/// Renders the given value verbatim into the end of the buffer, then replaces it with its
+/// sanitized form.
+///
+/// The buffer is held so that the unsanitized value is never flushed into the sink of a streaming
+/// buffer, and the value counts towards the maximum length of the buffer.
+fn render_sanitized(value: impl Render, buf: &mut Buffer, escape: fn(&str, &mut Buffer)) {
+    buf.hold(|buf| {
+        let start = buf.len();
+        let context = buf.set_context(EscapeContext::Url);
+
+        value.render(buf);
+        buf.set_context(context);
+
+        let value = String::from(&buf.as_str()[start..]);
+        buf.truncate(start);
+        escape(&value, buf);
+    })
+}
+
+/// Wraps a [`Render`], sanitizing its rendered value as a [`srcset`][1] attribute value.
+///
+/// This is the same as [`RenderUrl`] but for a comma-separated list of image candidates. See
+/// [`escape_srcset`] for details.
+///
+/// Element macros wrap the values of `srcset` attributes in this type automatically, unless the
+/// value is a literal, which is checked at compile time instead, or wrapped in [`raw!`](crate::raw).
+///
+/// [1]: https://developer.mozilla.org/en-US/docs/Web/HTML/Element/img#attr-srcset
+#[derive(Debug, Clone)]
+pub struct RenderSrcset<R>(
+    /// The value to sanitize.
+    pub R,
+)
+where
+    R: Render;
+
+impl<R> From<R> for RenderSrcset<R>
+where
+    R: Render,
+{
+    #[inline]
+    fn from(value: R) -> Self {
+        Self(value)
+    }
+}
+
+impl<R> Render for RenderSrcset<R>
+where
+    R: Render,
+{
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        render_sanitized(self.0, buf, escape_srcset)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, R> Render for &'a RenderSrcset<R>
+where
+    R: Render,
+    &'a R: Render,
+{
+    #[inline]
+    fn render(self, buf: &mut Buffer) {
+        RenderSrcset(&self.0).render(buf)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+/// Wraps a [`Render`] in [`RenderUrl`], sanitizing its rendered value as a URL.
+///
+/// This is a convenience macro that wraps the given expression in [`RenderUrl`]. It is useful
+/// for rendering URLs outside of URL attributes, such as in a `style` attribute or in the
+/// attributes of custom elements, which are not sanitized automatically.
+///
+/// # Expansion
+///
+/// ```ignore
+/// // url!($expr)
+/// {
+///     RenderUrl($expr)
+/// }
+/// ```
+///
+/// # Example
+///
+/// Values of known URL attributes are sanitized automatically. Safe URLs are percent-encoded
+/// where needed, and URLs with dangerous schemes are neutralized.
+///
+/// ```
+/// # use laby::*;
+/// let safe = "/search?q=laby docs&page=2";
+/// let unsafe_ = "javascript:alert(1)";
+///
+/// assert_eq!(
+///     render!(a!(href = safe), a!(href = unsafe_)),
+///     "<a href=\"/search?q=laby%20docs&amp;page=2\"></a><a href=\"about:invalid\"></a>"
+/// );
+/// ```
+///
+/// Other attributes can be sanitized explicitly using [`url!`](crate::url).
+///
+/// ```
+/// # use laby::*;
+/// let input = "javascript:alert(1)";
+/// let n = div!("data-href" = url!(input));
+///
+/// assert_eq!(render!(n), "<div data-href=\"about:invalid\"></div>");
+/// ```
+///
+/// Literal values are checked at compile time instead, and URLs with dangerous schemes are
+/// rejected.
+///
+/// ```compile_fail
+/// # use laby::*;
+/// let n = a!(href = "javascript:void(0)");
+/// ```
+///
+/// Known-safe values can opt out of sanitization using [`raw!`](crate::raw). The macro must be
+/// used directly as the attribute value.
+///
+/// ```
+/// # use laby::*;
+/// let n = a!(href = raw!("javascript:void(0)"));
+///
+/// assert_eq!(render!(n), "<a href=\"javascript:void(0)\"></a>");
+/// ```
+#[macro_export]
+macro_rules! url {
+    ($expr:expr) => {
+        $crate::RenderUrl($expr)
+    };
+}
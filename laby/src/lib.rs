@@ -48,9 +48,56 @@
 //! individually. The rest of this guide assumes that you have imported the necessary macros
 //! already.
 //!
+//! laby also provides macros for [SVG elements][14] in the [`svg`](mod@svg) module, such as
+//! [`svg::path!`] and [`svg::linearGradient!`], which can be nested in [`svg!`] to render inline
+//! icons. Inside [`svg!`], these elements can also be written without their path (e.g. `path!`).
+//! SVG elements without children are closed in their start tag (e.g. `<circle r="1"/>`),
+//! attribute names like `viewBox` keep their casing, and [`svg!`] includes the SVG namespace
+//! unless `xmlns` is given. SVG elements that share their names with HTML elements (`a`,
+//! `script`, `style` and `title`) are rendered using the HTML macros, and the `<use>` element is
+//! rendered using [`svg::r#use!`](svg/macro.use.html) because `use` is a keyword. Inside SVG and
+//! MathML, `<script>` and `<style>` are not raw text elements, so their contents are escaped like
+//! any other text, unless they are inside `<foreignObject>`.
+//!
+//! Likewise, [MathML elements][15] such as [`mathml::mi!`], [`mathml::mfrac!`] and
+//! [`mathml::msqrt!`] are provided in the [`mathml`] module, and can be nested in [`math!`] to
+//! render formulas, with the same closing rules as SVG elements. [`math!`] includes the MathML
+//! namespace unless `xmlns` is given.
+//!
+//! Rendering a value consumes it. Nodes created by element macros can also be rendered by
+//! reference (e.g. `render!(&layout)`) if all values captured by them can, so a tree that is built
+//! once can be rendered many times without cloning. See [`RenderRef`] for details.
+//!
 //! laby does not provide integration support for popular web frameworks. It returns a plain old
 //! [`String`][7] as the rendered result, so you are encouraged to write your own macro that writes
 //! that [`String`][7] to the response stream. Most web frameworks can do this out of the box.
+//! HTTP libraries that expect bytes can take the rendered [`Buffer`](internal::Buffer) as a
+//! `Vec<u8>`, or as `Bytes` with the `bytes` feature, without copying.
+//!
+//! For very large documents, the [`render_fmt!`] macro and the [`render_io!`] macro, which
+//! requires the `std` feature, write the output to a writer in chunks instead of returning one big
+//! [`String`][7], keeping memory usage bounded. To bound the size of the output itself, such as
+//! when rendering untrusted data, set a [maximum length](internal::Buffer::set_max_len) on the
+//! buffer, which reports an error instead of aborting the process when exceeded.
+//!
+//! Values that need to wait for data while rendering can implement [`AsyncRender`] and be
+//! rendered using [`render_stream`], which emits the output as a stream of chunks whenever
+//! rendering is suspended. With the `futures-core` feature, the returned [`RenderStream`]
+//! implements `Stream`, making it suitable as the body of a streaming HTTP response.
+//! Asynchronous values are embedded in markup with the [`awaited!`] macro, and values that are
+//! generic over the [`SendAsyncRender`] variant can be rendered on a multithreaded executor using
+//! [`render_stream_send`].
+//!
+//! Rendering can fail with an error by marking children that implement [`TryRender`], such as
+//! [`Result`], with the [`fallible!`] macro. Nodes with fallible children are rendered using the
+//! [`try_render!`] macro, which returns the first error instead of the rendered [`String`][7].
+//!
+//! Documents that must be well-formed XML, such as EPUB content, can be rendered as XHTML using
+//! the [`render_xhtml!`] macro. Void elements are then closed in the start tag (`<br/>`),
+//! boolean attributes are given their name as the value (`disabled="disabled"`), `<html>`
+//! includes the XHTML namespace, and the contents of `<script>` and `<style>` are escaped like
+//! any other text. Characters that are not allowed in XML 1.0 are replaced when escaping, see
+//! [`escape_xml`](internal::escape_xml). Use [`DocType::XHTML5`] to start such documents.
 //!
 //! # Basics
 //!
@@ -184,7 +231,14 @@
 //! ...`), not where the [`render!`] macro is called.
 //!
 //! Additionally, the apostrophes in the article contents are escaped with the HTML entity `&#39;`.
-//! laby escapes all templated expressions by default unless the [`raw!`] macro is used.
+//! laby escapes all templated expressions by default unless the [`raw!`] macro is used. Inside
+//! [`script!`] and [`style!`], where HTML entities are not recognized, only the sequences that
+//! would close the element early (e.g. `</script`) are escaped in string literals, so `a && b` is
+//! rendered as-is. Expressions in [`script!`] are additionally escaped as the contents of a
+//! JavaScript string, so they must be placed inside one (e.g. `script!("let x = '", x, "';")`).
+//! See [`EscapeContext`](internal::EscapeContext) for details. Values of URL attributes
+//! such as `href` and `src` are additionally sanitized, so that URLs with dangerous schemes like
+//! `javascript:` are never rendered. See [`url!`] for details.
 //!
 //! Under the hood, laby transforms the above code into code that looks something like this:
 //!
@@ -231,6 +285,43 @@
 //! will also be implemented for the generated struct, allowing for it to be rendered by
 //! [`render!`].
 //!
+//! Attribute names written as identifiers have their underscores replaced with hyphens, so
+//! `data_user_id = id` renders as `data-user-id="..."`. Keywords can be written as raw
+//! identifiers (e.g. `r#type = "text"`). To render an attribute name with underscores, write it
+//! as a string literal instead (e.g. `"my_attr" = value`).
+//!
+//! Attributes that are not known at compile time can be spread into a node using the `..` syntax
+//! (e.g. `div!(..attrs, "child")`), where `attrs` is an [`Attributes`] collection or any iterator
+//! of `(name, value)` pairs. Spread attributes are rendered in place alongside the other
+//! attributes of the node.
+//!
+//! Statically nested markup is validated at compile time. Elements must be nested according to
+//! the HTML content model (e.g. `<li>` must be in a list), which is an error otherwise. Attribute
+//! names written as identifiers are expected to be valid for their element, with `data-*`,
+//! `aria-*` and event handler attributes allowed everywhere, and literal values of enumerated
+//! attributes like `type` and `rel` are checked too. Unknown attributes and values are still
+//! rendered, but reported as warnings with suggestions. Procedural macros cannot emit warnings
+//! directly, so these are reported through the `deprecated` lint:
+//!
+//! ```
+//! # use laby::*;
+//! // warning: use of deprecated function `unknown_attribute`:
+//! //          unknown attribute `tpye` for `<input>`; did you mean `type`?
+//! let n = input!(tpye = "text");
+//! ```
+//!
+//! To opt out of validation, write the attribute name as a string literal, which is never
+//! validated (e.g. `"hx-get" = "/items"` or `"enabled" = bool!(x)`), or wrap a subtree of markup
+//! in the [`unchecked!`] macro. Prefer these over `#[allow(deprecated)]`, which would also hide
+//! genuine deprecations in the same scope.
+//!
+//! ```
+//! # use laby::*;
+//! // no warnings
+//! let n = input!("tpye" = "text");
+//! let m = form!(unchecked!(input!(tpye = "text")));
+//! ```
+//!
 //! # Componentization
 //!
 //! Writing a large template for rendering an entire HTML document quickly becomes unwieldy and
@@ -412,13 +503,19 @@
 //! [11]: https://developer.mozilla.org/
 //! [12]: https://github.com/mdn/content/blob/main/LICENSE.md
 //! [13]: laby
+//! [14]: https://developer.mozilla.org/en-US/docs/Web/SVG/Element
+//! [15]: https://developer.mozilla.org/en-US/docs/Web/MathML/Element
 #![no_std]
 #![deny(missing_docs)]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+mod attributes;
 mod doctype;
 mod helpers;
 
+pub use attributes::*;
 pub use doctype::*;
 pub use helpers::*;
 pub use laby_common::*;
@@ -427,13 +524,75 @@ pub use laby_macros::{
     article, aside, audio, b, base, bdi, bdo, blockquote, body, br, button, canvas, caption, cite,
     code, col, colgroup, data, datalist, dd, del, details, dfn, dialog, div, dl, dt, em, embed,
     fieldset, figcaption, figure, footer, form, h1, h2, h3, h4, h5, h6, head, header, hgroup, hr,
-    html, i, iframe, img, input, ins, kbd, label, legend, li, link, main, map, mark, menu,
+    html, i, iframe, img, input, ins, kbd, label, legend, li, link, main, map, mark, math, menu,
     menuitem, meta, meter, nav, noscript, object, ol, optgroup, option, output, p, param, picture,
     pre, progress, q, rb, rp, rt, rtc, ruby, s, samp, script, section, select, slot, small, source,
-    span, strong, style, sub, summary, sup, table, tbody, td, template, textarea, tfoot, th, thead,
-    time, title, tr, track, u, ul, var, video, wbr,
+    span, strong, style, sub, summary, sup, svg, table, tbody, td, template, textarea, tfoot, th,
+    thead, time, title, tr, track, u, ul, var, video, wbr,
 };
 
+/// Macros for [SVG elements](https://developer.mozilla.org/en-US/docs/Web/SVG/Element).
+///
+/// SVG elements are exported from this module instead of the crate root, because many of them
+/// have common names (e.g. `text!`, `line!` and `filter!`) that would otherwise collide with other
+/// macros, including [`line!`](core::line) of the standard library, when importing laby using
+/// `use laby::*`. Only [`svg!`](crate::svg!) is also exported from the crate root.
+///
+/// Elements can be invoked using their path (e.g. `svg::circle!`), or imported together with
+/// `use laby::svg::*` where no such collisions occur. Within markup, elements of this module are
+/// also recognized without their path inside an `<svg>` element.
+///
+/// SVG elements that share their names with HTML elements (`a`, `script`, `style` and `title`) are
+/// rendered using the HTML macros. The contents of `script!` and `style!` are escaped using HTML
+/// entities inside an `<svg>` element, because they are not raw text elements in SVG.
+///
+/// # Example
+///
+/// ```
+/// use laby::*;
+///
+/// let n = svg!(viewBox = "0 0 2 2", svg::circle!(r = 1), svg::line!(x2 = 2));
+/// let m = svg!(viewBox = "0 0 2 2", circle!(r = 1), line!(x2 = 2));
+///
+/// assert_eq!(render!(n), render!(m));
+/// ```
+pub mod svg {
+    pub use laby_macros::{
+        animate, animateMotion, animateTransform, circle, clipPath, defs, desc, ellipse, feBlend,
+        feColorMatrix, feComponentTransfer, feComposite, feConvolveMatrix, feDiffuseLighting,
+        feDisplacementMap, feDistantLight, feDropShadow, feFlood, feFuncA, feFuncB, feFuncG,
+        feFuncR, feGaussianBlur, feImage, feMerge, feMergeNode, feMorphology, feOffset,
+        fePointLight, feSpecularLighting, feSpotLight, feTile, feTurbulence, filter, foreignObject,
+        g, image, line, linearGradient, marker, mask, metadata, mpath, path, pattern, polygon,
+        polyline, r#use, radialGradient, rect, set, stop, svg, switch, symbol, text, textPath,
+        tspan, view,
+    };
+}
+
+/// Macros for [MathML elements](https://developer.mozilla.org/en-US/docs/Web/MathML/Element).
+///
+/// MathML elements are exported from this module instead of the crate root for the same reasons
+/// as the [`svg`](mod@svg) module. Only [`math!`](crate::math!) is also exported from the crate root, and
+/// elements of this module are also recognized without their path inside a `<math>` element.
+///
+/// # Example
+///
+/// ```
+/// use laby::*;
+///
+/// let n = math!(mathml::mfrac!(mathml::mn!(1), mathml::mi!("x")));
+/// let m = math!(mfrac!(mn!(1), mi!("x")));
+///
+/// assert_eq!(render!(n), render!(m));
+/// ```
+pub mod mathml {
+    pub use laby_macros::{
+        annotation, math, menclose, merror, mfrac, mi, mmultiscripts, mn, mo, mover, mpadded,
+        mphantom, mprescripts, mroot, mrow, ms, mspace, msqrt, mstyle, msub, msubsup, msup, mtable,
+        mtd, mtext, mtr, munder, munderover, semantics,
+    };
+}
+
 /// Generates a macro that calls a function with named arguments.
 ///
 /// Named arguments can be useful when a function accepts several arguments, because explicitly
@@ -714,6 +873,10 @@ pub use laby_macros::frag;
 /// This macro was named `frag_match` because it uses a set of [`Option`] variables for each
 /// variant and the [`frag!`][2] macro for rendering.
 ///
+/// Alternatively, each branch can return a boxed value as `Box<dyn BoxRender>`, using
+/// [`into_boxed`](BoxRender::into_boxed). This requires an allocation, but also works when the
+/// values are returned from functions or stored in collections.
+///
 /// # Expansion
 ///
 /// ```ignore
@@ -770,8 +933,8 @@ pub use laby_macros::frag_match;
 /// # Example
 ///
 /// The following example generates a class string with several values interpolated. Note that
-/// `four` is not included because it is [`None`], but the whitespace that delimits `four` is
-/// still rendered regardless.
+/// `four` is not included because it is [`None`]. Delimiters are only rendered between values
+/// that render something, so no stray whitespace is left behind.
 ///
 /// ```
 /// # use laby::*;
@@ -780,6 +943,104 @@ pub use laby_macros::frag_match;
 /// let six = 6;
 ///
 /// let s = classes!("one", two, "three", four, "five", six);
-/// assert_eq!(render!(s), "one two three  five 6");
+/// assert_eq!(render!(s), "one two three five 6");
+/// ```
+///
+/// A value can be followed by `=>` and a boolean condition, in which case it is only rendered if
+/// the condition evaluates to `true`.
+///
+/// ```
+/// # use laby::*;
+/// let active = true;
+/// let disabled = false;
+///
+/// let s = classes!("btn", "active" => active, "disabled" => disabled);
+/// assert_eq!(render!(s), "btn active");
 /// ```
 pub use laby_macros::classes;
+
+/// Wraps multiple CSS declarations into one value, with semicolons as the delimiter.
+///
+/// Each argument is a declaration of the form `property = value`, where the value implements
+/// [`Render`][1]. Property names can be written as identifiers, in which case underscores are
+/// replaced with hyphens (e.g. `font_size` becomes `font-size`), or as string literals, which
+/// allows custom properties like `"--accent"`. Invalid property names are rejected at compile
+/// time.
+///
+/// It is intended to be used to generate an interpolated string for the `style` attribute in an
+/// HTML markup.
+///
+/// Declarations whose values render nothing (e.g. [`None`] or empty strings) are omitted entirely,
+/// and semicolons are only rendered between declarations, so no stray delimiters are left behind.
+/// Like [`classes!`], a declaration can be followed by `=>` and a boolean condition.
+///
+/// Values are escaped so that they cannot terminate the declaration or the attribute, open a
+/// comment, or load a URL using `url()` (see [`escape_css_value`][2]). Literal values are rendered
+/// at compile time.
+///
+/// [1]: laby_common::Render
+/// [2]: laby_common::internal::escape_css_value
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let color = Some("red");
+/// let width: Option<&str> = None;
+/// let size = 12;
+///
+/// let n = div!(style = styles!(color = color, width = width, font_size = format!("{}px", size)));
+/// assert_eq!(render!(n), "<div style=\"color:red;font-size:12px\"></div>");
+/// ```
+///
+/// Values that would otherwise inject additional declarations are escaped.
+///
+/// ```
+/// # use laby::*;
+/// let color = "red; position: fixed";
+/// let highlighted = true;
+///
+/// let s = styles!("--accent" = color, font_weight = "bold" => highlighted);
+/// assert_eq!(render!(s), r"--accent:red\3b  position: fixed;font-weight:bold");
+/// ```
+pub use laby_macros::styles;
+
+/// Renders a [custom element][1] with the given tag name.
+///
+/// The first argument is the tag name of the element as a string literal, followed by the
+/// attributes and children of the element as in any other element macro. The tag name must be a
+/// [valid custom element name][2], which is checked at compile time. Custom elements nested in
+/// other markup are flattened like regular elements.
+///
+/// This macro is useful for rendering [web components][3]. Their shadow DOM can be rendered
+/// declaratively using a [`template!`] with the `shadowrootmode` attribute.
+///
+/// [1]: https://developer.mozilla.org/en-US/docs/Web/API/Web_components/Using_custom_elements
+/// [2]: laby_common::internal::is_valid_custom_element_name
+/// [3]: https://developer.mozilla.org/en-US/docs/Web/API/Web_components
+///
+/// # Example
+///
+/// ```
+/// # use laby::*;
+/// let name = "chiya";
+/// let n = el!(
+///     "user-card",
+///     class = "card",
+///     template!(shadowrootmode = "open", slot!()),
+///     span!(name),
+/// );
+///
+/// assert_eq!(
+///     render!(n),
+///     "<user-card class=\"card\"><template shadowrootmode=\"open\"><slot></slot></template><span>chiya</span></user-card>"
+/// );
+/// ```
+///
+/// Tag names that are not valid custom element names are rejected.
+///
+/// ```compile_fail
+/// # use laby::*;
+/// let n = el!("UserCard", "hello");
+/// ```
+pub use laby_macros::el;
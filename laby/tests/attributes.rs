@@ -63,3 +63,46 @@ fn variable_what() {
         "<div <div <span>laby</span>=\"what?\"></div>=\"what??\"></div>"
     );
 }
+
+#[test]
+fn hyphenated() {
+    let id = 5;
+    let label = "close";
+    let n = render!(button!(data_user_id = id, aria_label = label, "×"));
+    assert_eq!(
+        n,
+        "<button data-user-id=\"5\" aria-label=\"close\">×</button>"
+    );
+}
+
+#[test]
+fn hyphenated_literal() {
+    let n = render!(form!(accept_charset = "utf-8", aria_hidden = "true"));
+    assert_eq!(
+        n,
+        "<form accept-charset=\"utf-8\" aria-hidden=\"true\"></form>"
+    );
+}
+
+#[test]
+fn hyphenated_boolean() {
+    let busy = true;
+    let n = render!(div!(
+        data_busy = bool!(busy),
+        data_none = opt!(None::<&str>),
+        data_some = opt!(Some(1))
+    ));
+    assert_eq!(n, "<div data-busy data-some=\"1\"></div>");
+}
+
+#[test]
+fn raw_keyword() {
+    let n = render!(input!(r#type = "checkbox"));
+    assert_eq!(n, "<input type=\"checkbox\">");
+}
+
+#[test]
+fn underscores() {
+    let n = render!(div!("data_user_id" = 1));
+    assert_eq!(n, "<div data_user_id=\"1\"></div>");
+}
@@ -0,0 +1,62 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+use std::collections::HashMap;
+
+#[test]
+fn match_arms() {
+    fn node(value: &str) -> Box<dyn BoxRender + '_> {
+        match value {
+            "div" => div!().into_boxed(),
+            "span" => span!(value).into_boxed(),
+            _ => Box::new(img!()),
+        }
+    }
+
+    let n = render!(node("div"), node("span"), node("img"));
+    assert_eq!(n, "<div></div><span>span</span><img>");
+}
+
+#[test]
+fn vec() {
+    let name = String::from("b");
+    let v: Vec<Box<dyn BoxRender + '_>> = vec![li!("a").into_boxed(), li!(&name).into_boxed()];
+
+    let n = render!(ul!(v));
+    assert_eq!(n, "<ul><li>a</li><li>b</li></ul>");
+}
+
+#[test]
+fn table() {
+    type Component = fn(&str) -> Box<dyn BoxRender + Send + '_>;
+    let mut components: HashMap<&str, Component> = HashMap::new();
+
+    components.insert("bold", |s| Box::new(b!(s)));
+    components.insert("italic", |s| Box::new(i!(s)));
+
+    let n = render!(p!(components["bold"]("a"), components["italic"]("b")));
+    assert_eq!(n, "<p><b>a</b><i>b</i></p>");
+}
+
+#[test]
+fn send_sync() {
+    let value: Box<dyn BoxRender + Send + Sync> = Box::new(div!("a"));
+    let n = std::thread::spawn(move || render!(value)).join().unwrap();
+
+    assert_eq!(n, "<div>a</div>");
+}
+
+#[test]
+fn by_reference() {
+    let v: Vec<Box<dyn RenderRef>> = vec![Box::new(p!("a")), Box::new(String::from("b"))];
+
+    assert_eq!(render!(&v), "<p>a</p>b");
+    assert_eq!(render!(&v), "<p>a</p>b");
+    assert_eq!(render!(v), "<p>a</p>b");
+}
@@ -0,0 +1,66 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::{internal::Buffer, *};
+
+fn buffer() -> Buffer<'static> {
+    let mut buf = Buffer::new();
+    div!("a < b").render(&mut buf);
+    buf
+}
+
+#[test]
+fn into_bytes() {
+    assert_eq!(buffer().into_bytes(), b"<div>a &lt; b</div>");
+    assert_eq!(Vec::from(buffer()), b"<div>a &lt; b</div>");
+    assert_eq!(String::from(buffer()), "<div>a &lt; b</div>");
+    assert_eq!(Buffer::new().into_bytes(), b"");
+}
+
+#[test]
+fn into_bytes_no_copy() {
+    let buf = buffer();
+    let ptr = buf.as_ptr();
+
+    let bytes = buf.into_bytes();
+
+    assert_eq!(bytes.as_ptr(), ptr);
+}
+
+#[test]
+fn as_ref() {
+    fn bytes(value: impl AsRef<[u8]>) -> usize {
+        value.as_ref().len()
+    }
+
+    let buf = buffer();
+
+    assert_eq!(bytes(&buf), 19);
+    assert_eq!(buf.as_bytes(), b"<div>a &lt; b</div>");
+    assert_eq!(AsRef::<str>::as_ref(&buf), "<div>a &lt; b</div>");
+}
+
+#[test]
+fn deref() {
+    let buf = buffer();
+
+    assert!(buf.starts_with("<div>"));
+    assert_eq!(buf.find("&lt;"), Some(7));
+    assert_eq!(&*buf, "<div>a &lt; b</div>");
+}
+
+#[test]
+#[cfg(feature = "bytes")]
+fn bytes() {
+    let buf = buffer();
+    let ptr = buf.as_ptr();
+    let bytes = bytes::Bytes::from(buf);
+
+    assert_eq!(bytes, "<div>a &lt; b</div>");
+    assert_eq!(bytes.as_ptr(), ptr);
+}
@@ -35,7 +35,7 @@ fn escape() {
 fn nones() {
     let none: Option<&str> = None;
     let s = classes!(none, "1", none, none, "2", none, none, none, "3");
-    assert_eq!(render!(s), " 1   2    3");
+    assert_eq!(render!(s), "1 2 3");
 }
 
 #[test]
@@ -43,5 +43,66 @@ fn mixed() {
     let none: Option<&str> = None;
     let some = Some("some");
     let s = classes!("1", none, "2", "3", some, "4 5", none, some, none, "6", some, none);
-    assert_eq!(render!(s), "1  2 3 some 4 5  some  6 some ");
+    assert_eq!(render!(s), "1 2 3 some 4 5 some 6 some");
+}
+
+#[test]
+fn only_nones() {
+    let none: Option<&str> = None;
+    let s = classes!(none, none);
+    assert_eq!(render!(s), "");
+}
+
+#[test]
+fn empty_lits() {
+    let s = classes!("", "1", "", "2", "");
+    assert_eq!(render!(s), "1 2");
+}
+
+#[test]
+fn empty_strings() {
+    let empty = "";
+    let s = classes!(empty, "1", empty, "2", empty);
+    assert_eq!(render!(s), "1 2");
+}
+
+#[test]
+fn conditional() {
+    let active = true;
+    let disabled = false;
+    let s = classes!("btn", "active" => active, "disabled" => disabled);
+    assert_eq!(render!(s), "btn active");
+}
+
+#[test]
+fn conditional_first() {
+    let mut first = false;
+    let s = classes!("1" => first, "2");
+    assert_eq!(render!(s), "2");
+
+    first = true;
+    let s = classes!("1" => first, "2");
+    assert_eq!(render!(s), "1 2");
+}
+
+#[test]
+fn conditional_exprs() {
+    let x = "x";
+    let none: Option<&str> = None;
+    let s = classes!(none, x => true, x => false, none => true, "y");
+    assert_eq!(render!(s), "x y");
+}
+
+#[test]
+fn nested() {
+    let none: Option<&str> = None;
+    let s = classes!("1", classes!(none, none), "2", classes!(none, "3"));
+    assert_eq!(render!(s), "1 2 3");
+}
+
+#[test]
+fn attribute() {
+    let active = true;
+    let n = div!(class = classes!("a", None::<&str>, "b" => active));
+    assert_eq!(render!(n), "<div class=\"a b\"></div>");
 }
@@ -0,0 +1,78 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn vec() {
+    let v: Vec<_> = ["a", "b"].into_iter().map(|s| li!(s)).collect();
+    let n = render!(ul!(v));
+    assert_eq!(n, "<ul><li>a</li><li>b</li></ul>");
+}
+
+#[test]
+fn vec_empty() {
+    let v: Vec<&str> = vec![];
+    let n = render!(div!(v));
+    assert_eq!(n, "<div></div>");
+}
+
+#[test]
+fn array() {
+    let n = render!(div!(["a", "<", "b"]));
+    assert_eq!(n, "<div>a&lt;b</div>");
+}
+
+#[test]
+fn boxed_slice() {
+    let v: Box<[_]> = vec![1, 2, 3].into_boxed_slice();
+    let n = render!(div!(v));
+    assert_eq!(n, "<div>123</div>");
+}
+
+#[test]
+fn slice() {
+    struct Name(&'static str);
+
+    impl Render for &Name {
+        fn render(self, buffer: &mut internal::Buffer) {
+            self.0.render(buffer);
+        }
+    }
+
+    let v = [Name("a"), Name("b")];
+    let n = render!(div!(&v[..]));
+    assert_eq!(n, "<div>ab</div>");
+}
+
+#[test]
+fn tuple() {
+    let n = render!(div!((span!("a"), 1, "b")));
+    assert_eq!(n, "<div><span>a</span>1b</div>");
+}
+
+#[test]
+fn tuple_heterogeneous_list() {
+    fn items() -> (Option<&'static str>, Vec<u32>, [char; 2]) {
+        (Some("a"), vec![1], ['b', 'c'])
+    }
+
+    let n = render!(p!(items()));
+    assert_eq!(n, "<p>a1bc</p>");
+}
+
+#[test]
+fn result() {
+    let ok: Result<_, &str> = Ok(b!("ok"));
+    let n = render!(div!(ok));
+    assert_eq!(n, "<div><b>ok</b></div>");
+
+    let err: Result<&str, _> = Err(i!("error"));
+    let n = render!(div!(err));
+    assert_eq!(n, "<div><i>error</i></div>");
+}
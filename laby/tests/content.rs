@@ -0,0 +1,67 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn lists() {
+    let n = render!(
+        ul!(li!("1"), frag!(li!("2"), li!("3"))),
+        dl!(dt!("a"), dd!("b"))
+    );
+    assert_eq!(
+        n,
+        "<ul><li>1</li><li>2</li><li>3</li></ul><dl><dt>a</dt><dd>b</dd></dl>"
+    );
+}
+
+#[test]
+fn tables() {
+    let n = render!(table!(
+        thead!(tr!(th!("a"))),
+        tbody!(tr!(td!("1")), template!(tr!(td!("2")))),
+    ));
+
+    assert_eq!(
+        n,
+        "<table>\
+            <thead><tr><th>a</th></tr></thead>\
+            <tbody><tr><td>1</td></tr><template><tr><td>2</td></tr></template></tbody>\
+        </table>"
+    );
+}
+
+#[test]
+fn phrasing() {
+    let n = render!(p!(span!(b!("a")), a!(href = "/", svg!(g!()))));
+    assert_eq!(
+        n,
+        "<p><span><b>a</b></span><a href=\"/\"><svg xmlns=\"http://www.w3.org/2000/svg\"><g/></svg></a></p>"
+    );
+}
+
+#[test]
+fn dynamic() {
+    // elements that are not nested statically are not checked
+    let inner = div!();
+    let n = render!(p!(inner));
+    assert_eq!(n, "<p><div></div></p>");
+}
+
+#[test]
+fn unchecked() {
+    let n = render!(p!(unchecked!(div!(a!(a!())))));
+    assert_eq!(n, "<p><div><a><a></a></a></div></p>");
+}
+
+#[test]
+fn unchecked_expr() {
+    let s = "laby";
+    let n = render!(div!(unchecked!(s)));
+    assert_eq!(n, "<div>laby</div>");
+}
@@ -0,0 +1,71 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn normal() {
+    let n = render!(el!("user-card", class = "card", "laby"));
+    assert_eq!(n, "<user-card class=\"card\">laby</user-card>");
+}
+
+#[test]
+fn empty() {
+    let n = render!(el!("app-shell"));
+    assert_eq!(n, "<app-shell></app-shell>");
+}
+
+#[test]
+fn dynamic() {
+    let id = 5;
+    let name = "<name>";
+    let n = render!(el!("user-card", "data-id" = id, name));
+    assert_eq!(n, "<user-card data-id=\"5\">&lt;name&gt;</user-card>");
+}
+
+#[test]
+fn unicode() {
+    let n = render!(el!("math-α", "1"));
+    assert_eq!(n, "<math-α>1</math-α>");
+}
+
+#[test]
+fn nested() {
+    let n = div!(el!("app-shell", el!("user-card", "laby")), span!());
+
+    // nested custom elements are flattened into a single static string
+    assert_eq!(core::mem::size_of_val(&n), 0);
+    assert_eq!(
+        render!(n),
+        "<div><app-shell><user-card>laby</user-card></app-shell><span></span></div>"
+    );
+}
+
+#[test]
+fn shadow_root() {
+    let n = render!(el!(
+        "user-card",
+        template!(
+            shadowrootmode = "open",
+            style!(":host { display: block }"),
+            slot!(name = "title"),
+        ),
+        h2!(slot = "title", "laby"),
+    ));
+
+    assert_eq!(
+        n,
+        "<user-card>\
+            <template shadowrootmode=\"open\">\
+                <style>:host { display: block }</style>\
+                <slot name=\"title\"></slot>\
+            </template>\
+            <h2 slot=\"title\">laby</h2>\
+        </user-card>"
+    );
+}
@@ -0,0 +1,100 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn html5() {
+    let n = render!(DocType::HTML5);
+    assert_eq!(n, "<!DOCTYPE html>");
+}
+
+#[test]
+fn legacy() {
+    let n = render!(DocType::HTML401Strict);
+    assert_eq!(
+        n,
+        r#"<!DOCTYPE HTML PUBLIC "-//W3C//DTD HTML 4.01//EN" "http://www.w3.org/TR/html4/strict.dtd">"#
+    );
+
+    let n = render!(DocType::XHTML11);
+    assert_eq!(
+        n,
+        r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.1//EN" "http://www.w3.org/TR/xhtml11/DTD/xhtml11.dtd">"#
+    );
+}
+
+#[test]
+fn svg() {
+    let n = render!(DocType::XML, DocType::SVG11, svg!());
+    assert_eq!(
+        n,
+        r#"<?xml version="1.0" encoding="UTF-8"?><!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd"><svg xmlns="http://www.w3.org/2000/svg"/>"#
+    );
+}
+
+#[test]
+fn xml_declaration() {
+    let n = render!(XmlDeclaration {
+        encoding: None,
+        standalone: None,
+    });
+    assert_eq!(n, r#"<?xml version="1.0"?>"#);
+
+    let n = render!(XmlDeclaration {
+        encoding: Some("UTF-8"),
+        standalone: Some(false),
+    });
+    assert_eq!(
+        n,
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#
+    );
+}
+
+#[test]
+fn xml_declaration_invalid() {
+    let n = render!(XmlDeclaration {
+        encoding: Some("\"?><script>"),
+        standalone: None,
+    });
+    assert_eq!(n, r#"<?xml version="1.0" encoding="script"?>"#);
+}
+
+#[test]
+fn custom() {
+    let n = render!(CustomDocType {
+        name: "svg",
+        public: None,
+        system: Some("image.dtd"),
+    });
+    assert_eq!(n, r#"<!DOCTYPE svg SYSTEM "image.dtd">"#);
+
+    let n = render!(CustomDocType {
+        name: "html",
+        public: Some("-//IETF//DTD HTML//EN"),
+        system: None,
+    });
+    assert_eq!(n, r#"<!DOCTYPE html PUBLIC "-//IETF//DTD HTML//EN">"#);
+
+    let n = render!(CustomDocType {
+        name: "html",
+        public: None,
+        system: None,
+    });
+    assert_eq!(n, "<!DOCTYPE html>");
+}
+
+#[test]
+fn custom_invalid() {
+    let n = render!(CustomDocType {
+        name: "html><script>",
+        public: Some("\"><script>&"),
+        system: Some("a\"b>c&d"),
+    });
+    assert_eq!(n, r#"<!DOCTYPE htmlscript PUBLIC "script" "abc&d">"#);
+}
@@ -28,3 +28,23 @@ fn void_nested() {
     let n = render!(div!(class = "class", input!(class = "class")));
     assert_eq!(n, "<div class=\"class\"><input class=\"class\"></div>");
 }
+
+#[test]
+fn foreign_not_exported() {
+    // svg and mathml elements do not collide with other macros when importing `laby::*`
+    macro_rules! text {
+        () => {
+            "text"
+        };
+    }
+
+    let n = render!(div!(text!()), line!() > 0);
+    assert_eq!(n, "<div>text</div>true");
+
+    let n = render!(svg!(text!(), svg::line!()), math!(mi!("x")));
+    assert_eq!(
+        n,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\"><text/><line/></svg>\
+        <math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mi>x</mi></math>"
+    );
+}
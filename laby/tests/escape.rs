@@ -82,3 +82,119 @@ fn raw_attr_name() {
 
     assert_eq!(n, "<div <script>bad()</script>=\"what\"></div>");
 }
+
+#[test]
+fn script_literal() {
+    let n = render!(script!("if (a && b < c) { run('</script>'); }"));
+    assert_eq!(
+        n,
+        r"<script>if (a && b < c) { run('<\/script>'); }</script>"
+    );
+}
+
+#[test]
+fn script_child() {
+    let s = "a && b";
+    let n = render!(script!("let x = ", s, ";"));
+
+    assert_eq!(n, "<script>let x = a && b;</script>");
+}
+
+#[test]
+fn script_close() {
+    let s = "</script><script>bad()</SCRIPT>";
+    let n = render!(script!("let x = \"", s, "\";"));
+
+    assert_eq!(
+        n,
+        r#"<script>let x = "\u003C/script>\u003Cscript>bad()\u003C/SCRIPT>";</script>"#
+    );
+}
+
+#[test]
+fn script_comment() {
+    let s = "<!--<script>";
+    let n = render!(script!("'", s, "'"));
+
+    assert_eq!(n, r"<script>'\u003C!--\u003Cscript>'</script>");
+}
+
+#[test]
+fn script_string() {
+    let name = "\"; alert(1); //";
+    let n = render!(script!("var n = \"", name, "\";"));
+
+    assert_eq!(n, r#"<script>var n = "\"; alert(1); //";</script>"#);
+}
+
+#[test]
+fn script_quotes() {
+    let s = "'`${x}\\\n\r";
+    let n = render!(script!(s));
+
+    assert_eq!(n, r"<script>\'\`\${x}\\\n\r</script>");
+}
+
+#[test]
+fn script_line_terminators() {
+    let (s, c) = ("\u{2028}\u{2029}", '\u{2028}');
+    let n = render!(script!("'", s, c, '\u{2029}', "'"));
+
+    assert_eq!(n, r"<script>'\u2028\u2029\u2028\u2029'</script>");
+}
+
+#[test]
+fn script_code() {
+    let s = "a && b";
+    let n = render!(script!(
+        "let x = '",
+        s,
+        "'; if (x && y < z) run('</script>');"
+    ));
+
+    assert_eq!(
+        n,
+        r"<script>let x = 'a && b'; if (x && y < z) run('<\/script>');</script>"
+    );
+}
+
+#[test]
+fn script_split() {
+    let a = "/script>";
+    let b = "!--";
+    let n = render!(script!("'<", a, "';'<", b, "'"));
+
+    assert_eq!(n, r"<script>'<\u002Fscript>';'<\u0021--'</script>");
+}
+
+#[test]
+fn script_attr() {
+    let s = "a && b";
+    let n = render!(script!(id = s, s));
+
+    assert_eq!(n, "<script id=\"a &amp;&amp; b\">a && b</script>");
+}
+
+#[test]
+fn script_sibling() {
+    let s = "a && b";
+    let n = render!(div!(script!(s), s));
+
+    assert_eq!(n, "<div><script>a && b</script>a &amp;&amp; b</div>");
+}
+
+#[test]
+fn style_child() {
+    let s = "a > b::after { content: '</style>' }";
+    let n = render!(style!(s));
+
+    assert_eq!(n, r"<style>a > b::after { content: '<\/style>' }</style>");
+}
+
+#[test]
+fn style_split() {
+    let s = "</";
+    let n = render!(style!(s, "style>"));
+
+    assert_eq!(n, r"<style></\73 tyle></style>");
+}
@@ -66,3 +66,10 @@ fn custom() {
     let n = render!(iter!(", ", v));
     assert_eq!(n, "1, 2, 3, 4, 5");
 }
+
+#[test]
+fn custom_nones() {
+    let v = [None, Some(1), None, Some(2), None];
+    let n = render!(iter!(", ", v));
+    assert_eq!(n, "1, 2");
+}
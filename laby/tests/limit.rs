@@ -0,0 +1,182 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::{internal::*, *};
+
+#[test]
+fn under_limit() {
+    let mut buf = Buffer::new();
+    buf.set_max_len(16);
+
+    div!("hello").render(&mut buf);
+
+    assert_eq!(buf.error(), None);
+    assert_eq!(buf.try_into_string().unwrap(), "<div>hello</div>");
+}
+
+#[test]
+fn over_limit() {
+    let items: Vec<_> = (0..10_000).map(|i| format!("item {}", i)).collect();
+    let mut buf = Buffer::new();
+    buf.set_max_len(1000);
+
+    ul!(iter!(items.iter().map(|item| li!(item)))).render(&mut buf);
+
+    assert_eq!(
+        buf.error(),
+        Some(BufferError::LimitExceeded { max_len: 1000 })
+    );
+    assert!(buf.capacity() < 2000);
+    assert_eq!(
+        buf.try_into_string(),
+        Err(BufferError::LimitExceeded { max_len: 1000 })
+    );
+}
+
+#[test]
+fn exact_limit() {
+    let mut buf = Buffer::new();
+    buf.set_max_len(5);
+
+    buf.push_str("hello");
+    assert_eq!(buf.error(), None);
+
+    buf.push('!');
+    assert_eq!(buf.error(), Some(BufferError::LimitExceeded { max_len: 5 }));
+}
+
+#[test]
+fn preallocated() {
+    let mut buf = Buffer::with_capacity(100);
+    buf.set_max_len(10);
+
+    buf.push_str("hello, world!");
+    assert_eq!(
+        buf.error(),
+        Some(BufferError::LimitExceeded { max_len: 10 })
+    );
+}
+
+#[test]
+fn try_reserve() {
+    let mut buf = Buffer::new();
+    assert_eq!(buf.try_reserve(16), Ok(()));
+    assert!(buf.capacity() >= 16);
+
+    assert_eq!(
+        buf.try_reserve(usize::MAX),
+        Err(BufferError::CapacityOverflow)
+    );
+
+    buf.set_max_len(32);
+    buf.push_str("hello");
+
+    assert_eq!(buf.try_reserve(27), Ok(()));
+    assert_eq!(
+        buf.try_reserve(28),
+        Err(BufferError::LimitExceeded { max_len: 32 })
+    );
+
+    // failed reservations do not fail the buffer
+    assert_eq!(buf.error(), None);
+    assert_eq!(buf.as_str(), "hello");
+}
+
+#[test]
+fn stream() {
+    let mut chunks = Vec::new();
+
+    let error = Buffer::stream(8, &mut |chunk: &str| chunks.push(chunk.to_owned()), |buf| {
+        buf.set_max_len(20);
+
+        for _ in 0..10 {
+            buf.push_str("hello, ");
+        }
+
+        buf.error()
+    });
+
+    assert_eq!(error, Some(BufferError::LimitExceeded { max_len: 20 }));
+    assert!(chunks.concat().len() <= 21);
+}
+
+#[test]
+fn pool_reset() {
+    let mut pool = BufferPool::new();
+    let mut buf = pool.get();
+
+    buf.set_max_len(1);
+    buf.push_str("hello");
+    pool.put(buf);
+
+    let buf = pool.get();
+    assert_eq!(buf.max_len(), usize::MAX);
+    assert_eq!(buf.error(), None);
+}
+
+#[test]
+fn display() {
+    assert_eq!(
+        BufferError::LimitExceeded { max_len: 10 }.to_string(),
+        "buffer exceeded the maximum length of 10 bytes"
+    );
+}
+
+#[test]
+fn no_allocation() {
+    let mut buf = Buffer::new();
+    buf.set_max_len(10);
+
+    buf.push_str("hello");
+    buf.push_str(&"a".repeat(1 << 20));
+
+    assert_eq!(
+        buf.error(),
+        Some(BufferError::LimitExceeded { max_len: 10 })
+    );
+    assert!(buf.capacity() <= 10);
+}
+
+#[test]
+fn keep_contents() {
+    let mut buf = Buffer::new();
+    buf.set_max_len(8);
+
+    buf.push_str("hello");
+    buf.push_str(", world");
+    buf.push_str("!!!!");
+
+    assert_eq!(buf.error(), Some(BufferError::LimitExceeded { max_len: 8 }));
+    assert_eq!(buf.as_str(), "hello");
+}
+
+#[test]
+fn near_limit() {
+    // short strings and numbers are not rejected by the estimated size of their output
+    let (s, i, f) = ("a", 1, 2.5);
+    let mut buf = Buffer::new();
+    buf.set_max_len(12);
+
+    p!(s, i, f).render(&mut buf);
+    assert_eq!(buf.try_into_string().unwrap(), "<p>a12.5</p>");
+}
+
+#[test]
+fn reserve_failed() {
+    let mut buf = Buffer::new();
+    buf.set_max_len(8);
+
+    buf.push_str("hello");
+    buf.push_str(", world");
+    buf.reserve(100);
+
+    // direct writes require the reserved capacity, but the contents are kept
+    assert!(buf.capacity() - buf.len() >= 100);
+    assert_eq!(buf.as_str(), "hello");
+    assert_eq!(buf.error(), Some(BufferError::LimitExceeded { max_len: 8 }));
+}
@@ -0,0 +1,99 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::{mathml::*, *};
+
+#[test]
+fn namespace() {
+    let n = render!(math!(mi!("x")));
+    assert_eq!(
+        n,
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mi>x</mi></math>"
+    );
+}
+
+#[test]
+fn explicit_namespace() {
+    let n = render!(math!(xmlns = "http://www.w3.org/1998/Math/MathML", mn!(1)));
+    assert_eq!(
+        n,
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mn>1</mn></math>"
+    );
+}
+
+#[test]
+fn self_closing() {
+    let n = render!(mspace!(width = "1em"));
+    assert_eq!(n, "<mspace width=\"1em\"/>");
+}
+
+#[test]
+fn escape() {
+    let op = "<";
+    let n = render!(mrow!(mi!("a"), mo!(op), mi!("b")));
+    assert_eq!(n, "<mrow><mi>a</mi><mo>&lt;</mo><mi>b</mi></mrow>");
+}
+
+#[test]
+fn formula() {
+    let n = p!(
+        "x = ",
+        math!(
+            display = "block",
+            mfrac!(
+                mrow!(
+                    mo!("-"),
+                    mi!("b"),
+                    mo!("±"),
+                    msqrt!(msup!(mi!("b"), mn!(2)))
+                ),
+                mrow!(mn!(2), mi!("a")),
+            ),
+        ),
+    );
+
+    // nested mathml markup is flattened into a single static string
+    assert_eq!(core::mem::size_of_val(&n), 0);
+    assert_eq!(
+        render!(n),
+        "<p>x = \
+            <math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">\
+                <mfrac>\
+                    <mrow><mo>-</mo><mi>b</mi><mo>±</mo><msqrt><msup><mi>b</mi><mn>2</mn></msup></msqrt></mrow>\
+                    <mrow><mn>2</mn><mi>a</mi></mrow>\
+                </mfrac>\
+            </math>\
+        </p>"
+    );
+}
+
+#[test]
+fn style() {
+    let user = "</style><img src=x onerror=alert(1)>";
+    let s = style!(user);
+    let n = render!(math!(style!(user), mrow!(s)));
+
+    // style is not a raw text element in mathml
+    assert_eq!(
+        n,
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">\
+            <style>&lt;/style&gt;&lt;img src=x onerror=alert(1)&gt;</style>\
+            <mrow><style>&lt;/style&gt;&lt;img src=x onerror=alert(1)&gt;</style></mrow>\
+        </math>"
+    );
+
+    // the flag is restored after the math element
+    let s = || style!(user);
+    assert_eq!(
+        render!(math!(mrow!(s())), s()),
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">\
+            <mrow><style>&lt;/style&gt;&lt;img src=x onerror=alert(1)&gt;</style></mrow>\
+        </math>\
+        <style><\\/style><img src=x onerror=alert(1)></style>"
+    );
+}
@@ -0,0 +1,79 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn some() {
+    let n = render!(div!(title = opt!(Some("laby"))));
+    assert_eq!(n, "<div title=\"laby\"></div>");
+}
+
+#[test]
+fn none() {
+    let x: Option<&str> = None;
+    let n = render!(div!(title = opt!(x)));
+    assert_eq!(n, "<div></div>");
+}
+
+#[test]
+fn variable() {
+    let mut x = Some(1);
+    let n = render!(div!(tabindex = opt!(x)));
+    assert_eq!(n, "<div tabindex=\"1\"></div>");
+
+    x = None;
+    let n = render!(div!(tabindex = opt!(x)));
+    assert_eq!(n, "<div></div>");
+}
+
+#[test]
+fn escaped() {
+    let x = Some("\"laby\"");
+    let n = render!(div!(title = opt!(x)));
+    assert_eq!(n, "<div title=\"&quot;laby&quot;\"></div>");
+}
+
+#[test]
+fn dynamic_name() {
+    let name = "data-id";
+    let x = Some(5);
+    let n = render!(div!({ name } = opt!(x)));
+    assert_eq!(n, "<div data-id=\"5\"></div>");
+
+    let x: Option<i32> = None;
+    let n = render!(div!({ name } = opt!(x)));
+    assert_eq!(n, "<div></div>");
+}
+
+#[test]
+fn url() {
+    let x = Some("javascript:alert(1)");
+    let n = render!(a!(href = opt!(x)));
+    assert_eq!(n, "<a href=\"about:invalid\"></a>");
+}
+
+#[test]
+fn none_without_marker() {
+    let x: Option<&str> = None;
+    let n = render!(div!(title = x));
+    assert_eq!(n, "<div title=\"\"></div>");
+}
+
+#[test]
+fn mixed() {
+    let x: Option<&str> = None;
+    let n = render!(input!(
+        id = "a",
+        title = opt!(x),
+        disabled = bool!(true),
+        value = opt!(Some("b")),
+    ));
+
+    assert_eq!(n, "<input id=\"a\" disabled value=\"b\">");
+}
@@ -0,0 +1,78 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::{internal::*, *};
+
+#[test]
+fn into_buffer() {
+    let mut buf = Buffer::new();
+
+    assert_eq!(render_into!(buf, span!(), "b"), "<span></span>b");
+    let capacity = buf.capacity();
+
+    assert_eq!(render_into!(buf, div!("a")), "<div>a</div>");
+    assert_eq!(buf.capacity(), capacity);
+}
+
+#[test]
+fn render_into_reference() {
+    let mut buf = Buffer::new();
+    let buf = &mut buf;
+
+    assert_eq!(render_into!(*buf, "a < b"), "a &lt; b");
+}
+
+#[test]
+fn reuse() {
+    let mut pool = BufferPool::new();
+    let s = pool.render(div!("a"), |s| s.to_owned());
+
+    assert_eq!(s, "<div>a</div>");
+    assert_eq!(pool.len(), 1);
+
+    let mut buf = pool.get();
+    assert!(buf.is_empty());
+    assert!(buf.capacity() >= s.len());
+    assert!(pool.is_empty());
+
+    buf.set_context(EscapeContext::Script);
+    pool.put(buf);
+    assert_eq!(pool.get().context(), EscapeContext::Html);
+}
+
+#[test]
+fn trim() {
+    let mut pool = BufferPool::with_limits(1, 64);
+    let mut buf = pool.get();
+
+    render_into("a".repeat(1000), &mut buf);
+    assert!(buf.capacity() >= 1000);
+
+    pool.put(buf);
+    assert_eq!(pool.get().capacity(), 64);
+}
+
+#[test]
+fn limit() {
+    let mut pool = BufferPool::with_limits(1, 64);
+
+    pool.put(Buffer::new());
+    pool.put(Buffer::new());
+    assert_eq!(pool.len(), 1);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn thread_local() {
+    let s = with_pooled_buffer(|buf| {
+        let inner = with_pooled_buffer(|buf| render_into!(*buf, "b").to_owned());
+        render_into!(*buf, p!(inner)).to_owned()
+    });
+
+    assert_eq!(s, "<p>b</p>");
+}
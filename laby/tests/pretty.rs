@@ -0,0 +1,148 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn document() {
+    let s = render_pretty!(
+        DocType::HTML5,
+        html!(
+            head!(meta!(charset = "utf-8"), title!("laby")),
+            body!(div!(class = "a", h1!("title"), p!("text"))),
+        ),
+    );
+
+    assert_eq!(
+        s,
+        "\
+<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset=\"utf-8\">
+    <title>laby</title>
+  </head>
+  <body>
+    <div class=\"a\">
+      <h1>title</h1>
+      <p>text</p>
+    </div>
+  </body>
+</html>"
+    );
+}
+
+#[test]
+fn inline() {
+    let s = render_pretty!(p!("a ", b!("b"), i!(" c "), br!(), "d"));
+    assert_eq!(s, "<p>a <b>b</b><i> c </i><br>d</p>");
+}
+
+#[test]
+fn mixed() {
+    let s = render_pretty!(div!("before ", span!("inline"), p!("block"), " after"));
+    assert_eq!(
+        s,
+        "\
+<div>
+  before <span>inline</span>
+  <p>block</p>
+  after
+</div>"
+    );
+}
+
+#[test]
+fn whitespace_sensitive() {
+    let s = render_pretty!(div!(
+        pre!(code!("fn main() {\n    <div>\n}")),
+        textarea!("  a\n  b  "),
+    ));
+
+    assert_eq!(
+        s,
+        "\
+<div>
+  <pre><code>fn main() {
+    &lt;div&gt;
+}</code></pre>
+  <textarea>  a
+  b  </textarea>
+</div>"
+    );
+}
+
+#[test]
+fn raw_text() {
+    let s = render_pretty!(head!(
+        script!("if (a < b) { x('<div><p>') }"),
+        style!("a > b { color: red }"),
+    ));
+
+    assert_eq!(
+        s,
+        "\
+<head>
+  <script>if (a < b) { x('<div><p>') }</script>
+  <style>a > b { color: red }</style>
+</head>"
+    );
+}
+
+#[test]
+fn tables() {
+    let s = render_pretty!(table!(tbody!(tr!(td!(1), td!(2)))));
+    assert_eq!(
+        s,
+        "\
+<table>
+  <tbody>
+    <tr>
+      <td>1</td>
+      <td>2</td>
+    </tr>
+  </tbody>
+</table>"
+    );
+}
+
+#[test]
+fn comments() {
+    let s = render_pretty!(div!(raw!("<!-- <p> -->"), p!()));
+    assert_eq!(s, "<div>\n  <!-- <p> -->\n  <p></p>\n</div>");
+}
+
+#[test]
+fn unbalanced() {
+    let s = render_pretty!(raw!("<div><p>a</span><div>b</div>"));
+    assert_eq!(s, "<div>\n  <p>\n    a</span>\n    <div>b</div>");
+}
+
+#[test]
+fn attributes() {
+    let s = render_pretty!(div!(title = "a > b", p!()));
+    assert_eq!(s, "<div title=\"a &gt; b\">\n  <p></p>\n</div>");
+}
+
+#[test]
+fn svg() {
+    let s = render_pretty!(div!(svg!(path!(d = "M0 0")), p!()));
+    assert_eq!(
+        s,
+        "<div>\n  <svg xmlns=\"http://www.w3.org/2000/svg\"><path d=\"M0 0\"/></svg>\n  <p></p>\n</div>"
+    );
+}
+
+#[test]
+fn lists() {
+    let s = render_pretty!(ul!(li!("a"), li!(ol!(li!("b")))));
+    assert_eq!(
+        s,
+        "<ul>\n  <li>a</li>\n  <li>\n    <ol>\n      <li>b</li>\n    </ol>\n  </li>\n</ul>"
+    );
+}
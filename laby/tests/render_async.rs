@@ -0,0 +1,242 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::{internal::BufferError, *};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+/// Future that suspends once before completing.
+struct Suspend(bool);
+
+impl Future for Suspend {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+fn collect<F: Future<Output = ()>>(mut stream: RenderStream<F>) -> Vec<String> {
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut chunks = vec![];
+
+    loop {
+        match stream.poll_chunk(&mut cx) {
+            Poll::Ready(Some(chunk)) => chunks.push(chunk),
+            Poll::Ready(None) => break chunks,
+            Poll::Pending => {}
+        }
+    }
+}
+
+struct Page;
+
+impl AsyncRender for Page {
+    async fn render_async(self, buffer: AsyncBuffer) {
+        buffer.render(head!(title!("laby")));
+        Suspend(false).await;
+        buffer.render(body!("loaded"));
+    }
+}
+
+impl SendAsyncRender for Page {
+    fn render_async_send(self, buffer: AsyncBuffer) -> impl Future<Output = ()> + Send {
+        self.render_async(buffer)
+    }
+}
+
+#[test]
+fn sync() {
+    let chunks = collect(render_stream(div!("laby")));
+    assert_eq!(chunks, ["<div>laby</div>"]);
+}
+
+#[test]
+fn empty() {
+    let chunks = collect(render_stream(frag!()));
+    assert!(chunks.is_empty());
+}
+
+#[test]
+fn chunked() {
+    let chunks = collect(render_stream(Page));
+    assert_eq!(
+        chunks,
+        ["<head><title>laby</title></head>", "<body>loaded</body>"]
+    );
+}
+
+#[test]
+fn nested() {
+    struct Document;
+
+    impl AsyncRender for Document {
+        async fn render_async(self, buffer: AsyncBuffer) {
+            buffer.render(raw!("<html>"));
+            Page.render_async(buffer.clone()).await;
+            buffer.render(raw!("</html>"));
+        }
+    }
+
+    let chunks = collect(render_stream(Document));
+    assert_eq!(
+        chunks,
+        [
+            "<html><head><title>laby</title></head>",
+            "<body>loaded</body></html>"
+        ]
+    );
+}
+
+#[test]
+fn send() {
+    fn assert_send<T: Send>(_: T) {}
+
+    assert_send(render_stream(div!("laby")));
+    assert_send(render_stream(Page));
+}
+
+#[test]
+fn send_generic() {
+    fn assert_send<T: Send>(_: T) {}
+
+    fn spawn<R: SendAsyncRender>(value: R) {
+        assert_send(render_stream_send(value));
+    }
+
+    spawn(div!("laby"));
+    spawn(Page);
+    spawn(html!(awaited!(Page)));
+}
+
+#[test]
+fn awaited() {
+    let chunks = collect(render_stream(html!(
+        lang = "en",
+        awaited!(Page),
+        body!("laby"),
+    )));
+
+    assert_eq!(
+        chunks,
+        [
+            "<html lang=\"en\"><head><title>laby</title></head>",
+            "<body>loaded</body><body>laby</body></html>"
+        ]
+    );
+}
+
+#[test]
+fn awaited_nested() {
+    let n = div!(awaited!(Page), "a");
+    let chunks = collect(render_stream_send(frag!(awaited!(n), "b")));
+
+    assert_eq!(
+        chunks,
+        [
+            "<div><head><title>laby</title></head>",
+            "<body>loaded</body>a</div>b"
+        ]
+    );
+}
+
+#[test]
+fn awaited_script() {
+    struct Data;
+
+    impl AsyncRender for Data {
+        async fn render_async(self, buffer: AsyncBuffer) {
+            Suspend(false).await;
+            buffer.render("</script>");
+        }
+    }
+
+    let chunks = collect(render_stream(script!(awaited!(Data))));
+    assert_eq!(chunks, ["<script>", "\\u003C/script></script>"]);
+}
+
+#[test]
+fn awaited_script_straddle() {
+    struct Data;
+
+    impl AsyncRender for Data {
+        async fn render_async(self, buffer: AsyncBuffer) {
+            buffer.render(raw!("</scr"));
+            Suspend(false).await;
+            buffer.render("ipt>");
+        }
+    }
+
+    let chunks = collect(render_stream(script!(awaited!(Data))));
+    assert_eq!(chunks, ["<script></scr", "\\u0069pt></script>"]);
+}
+
+#[test]
+fn xhtml() {
+    struct Document;
+
+    impl AsyncRender for Document {
+        async fn render_async(self, buffer: AsyncBuffer) {
+            buffer.with(|buffer| buffer.set_xhtml(true));
+            buffer.render(br!());
+            Suspend(false).await;
+            buffer.render(frag!(br!(), input!(disabled = bool!(true))));
+        }
+    }
+
+    let chunks = collect(render_stream(Document));
+    assert_eq!(chunks, ["<br/>", "<br/><input disabled=\"disabled\"/>"]);
+}
+
+#[test]
+fn limit() {
+    struct Document;
+
+    impl AsyncRender for Document {
+        async fn render_async(self, buffer: AsyncBuffer) {
+            buffer.with(|buffer| buffer.set_max_len(8));
+            buffer.render("hello");
+            Suspend(false).await;
+            buffer.render(", world");
+
+            buffer.with(|buffer| {
+                assert_eq!(buffer.written(), 5);
+                assert_eq!(
+                    buffer.error(),
+                    Some(BufferError::LimitExceeded { max_len: 8 })
+                );
+            });
+        }
+    }
+
+    let chunks = collect(render_stream(Document));
+    assert_eq!(chunks, ["hello"]);
+}
+
+#[test]
+fn reentrant() {
+    let buffer = AsyncBuffer::new();
+
+    buffer.with(|inner| {
+        inner.push_str("a");
+        buffer.render("b");
+        inner.push_str("c");
+    });
+
+    buffer.render("d");
+    assert_eq!(buffer.with(|buffer| buffer.as_str().to_owned()), "acbd");
+}
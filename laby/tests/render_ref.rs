@@ -0,0 +1,97 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn node() {
+    let title = String::from("laby");
+    let n = div!(class = "a", h1!(title), p!(1, 'b', 2.5));
+
+    assert_eq!(
+        render!(&n),
+        "<div class=\"a\"><h1>laby</h1><p>1b2.5</p></div>"
+    );
+    assert_eq!(render!(&n), render!(n));
+}
+
+#[test]
+fn nested() {
+    let layout = html!(body!(main!(p!("a < b"))));
+    let page = |title: &str| render!(DocType::HTML5, &layout, title);
+
+    assert_eq!(
+        page("x"),
+        "<!DOCTYPE html><html><body><main><p>a &lt; b</p></main></body></html>x"
+    );
+    assert_eq!(page("y").len(), page("x").len());
+}
+
+#[test]
+fn attributes() {
+    let url = "javascript:alert(1)";
+    let id = String::from("b");
+    let hidden = true;
+    let attrs = Attributes::new().with("data-x", "1");
+    let n = a!(
+        href = url,
+        id = id,
+        hidden = bool!(hidden),
+        title = opt!(Some("c")),
+        ..attrs
+    );
+
+    assert_eq!(
+        render!(&n),
+        "<a href=\"about:invalid\" id=\"b\" hidden title=\"c\" data-x=\"1\"></a>"
+    );
+}
+
+#[test]
+fn classes() {
+    let active = true;
+    let name = String::from("b");
+    let n = div!(class = classes!("a", name, "active" => active));
+
+    assert_eq!(render!(&n), "<div class=\"a b active\"></div>");
+    assert_eq!(render!(&n), "<div class=\"a b active\"></div>");
+}
+
+#[test]
+fn collections() {
+    let v = vec![Some("a"), None, Some("b")];
+    let t = (1, String::from("c"), ['d', 'e']);
+    let r: Result<u8, &str> = Err("f");
+
+    assert_eq!(render!(&v, &t, &r), "ab1cdef");
+    assert_eq!(render!(v, t, r), "ab1cdef");
+}
+
+#[test]
+fn nested_references() {
+    let inner = span!("a");
+    let n = div!(&inner, &inner);
+
+    assert_eq!(render!(&n), "<div><span>a</span><span>a</span></div>");
+}
+
+#[test]
+fn render_ref() {
+    fn render_all<R: RenderRef + ?Sized>(values: &[&R]) -> String {
+        let mut buffer = internal::Buffer::new();
+
+        for value in values {
+            value.render_ref(&mut buffer);
+        }
+
+        buffer.into_string()
+    }
+
+    let n = p!("a");
+    assert_eq!(render_all(&[&n, &n]), "<p>a</p><p>a</p>");
+}
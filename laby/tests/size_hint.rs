@@ -0,0 +1,84 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn static_node() {
+    let n = div!(class = "a", p!("b"), br!());
+    assert_eq!(n.size_hint(), "<div class=\"a\"><p>b</p><br></div>".len());
+}
+
+#[test]
+fn dynamic() {
+    let s = String::from("abc");
+    let n = p!(title = s.as_str(), s.as_str());
+
+    assert_eq!(n.size_hint(), "<p title=\"abc\">abc</p>".len());
+    assert_eq!(n.size_hint(), render!(n).len());
+}
+
+#[test]
+fn nested() {
+    let s = "ab";
+    let n = ul!(li!(s), li!(s), Some(li!(s)));
+
+    assert_eq!(
+        n.size_hint(),
+        "<ul><li>ab</li><li>ab</li><li>ab</li></ul>".len()
+    );
+}
+
+#[test]
+fn by_reference() {
+    let s = "ab";
+    let n = div!(s);
+
+    assert_eq!(Render::size_hint(&&n), n.size_hint());
+    assert_eq!(Render::size_hint(&&n), 13);
+}
+
+#[test]
+fn collections() {
+    let v = vec!["a", "bc"];
+    let t = ('d', String::from("ef"), Some("g"));
+
+    assert_eq!(v.size_hint(), 3);
+    assert_eq!(t.size_hint(), 4);
+    assert_eq!(div!(v, t).size_hint(), 18);
+}
+
+#[test]
+fn raw() {
+    assert_eq!(raw!("<b>").size_hint(), 3);
+}
+
+#[test]
+fn preallocate() {
+    let s = render!(div!(p!("abc")));
+
+    assert_eq!(s, "<div><p>abc</p></div>");
+    assert_eq!(s.capacity(), s.len());
+}
+
+#[test]
+fn unknown() {
+    // values without a size hint fall back to the default capacity
+    let s = render!(1);
+    assert_eq!(s.capacity(), 16384);
+}
+
+#[test]
+fn boxed() {
+    let n: Box<dyn BoxRender> = p!("abc").into_boxed();
+    assert_eq!(n.size_hint(), 10);
+
+    let n: Box<dyn RenderRef> = Box::new(p!("abc"));
+    assert_eq!(n.size_hint(), 10);
+    assert_eq!(Render::size_hint(&&n), 10);
+}
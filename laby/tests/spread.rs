@@ -0,0 +1,134 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+use std::collections::BTreeMap;
+
+#[test]
+fn array() {
+    let attrs = [("id", "a"), ("title", "b")];
+    let n = render!(div!(..attrs));
+
+    assert_eq!(n, "<div id=\"a\" title=\"b\"></div>");
+}
+
+#[test]
+fn vec() {
+    let attrs = vec![("data-id", 5), ("tabindex", -1)];
+    let n = render!(div!(..attrs, "x"));
+
+    assert_eq!(n, "<div data-id=\"5\" tabindex=\"-1\">x</div>");
+}
+
+#[test]
+fn map() {
+    let mut attrs = BTreeMap::new();
+    attrs.insert("aria-label", String::from("close"));
+    attrs.insert("aria-hidden", String::from("true"));
+
+    let n = render!(span!(..attrs));
+
+    assert_eq!(n, "<span aria-hidden=\"true\" aria-label=\"close\"></span>");
+}
+
+#[test]
+fn empty() {
+    let attrs: [(&str, &str); 0] = [];
+    let n = render!(div!(..attrs));
+
+    assert_eq!(n, "<div></div>");
+}
+
+#[test]
+fn order() {
+    let attrs = [("b", "2")];
+    let n = render!(div!("a" = "1", ..attrs, "c" = "3"));
+
+    assert_eq!(n, "<div a=\"1\" b=\"2\" c=\"3\"></div>");
+}
+
+#[test]
+fn multiple() {
+    let a = [("a", "1")];
+    let b = Attributes::new().with("b", "2");
+    let n = render!(div!(..a, ..b));
+
+    assert_eq!(n, "<div a=\"1\" b=\"2\"></div>");
+}
+
+#[test]
+fn nested() {
+    let attrs = [("id", "inner")];
+    let n = render!(div!(span!(..attrs)));
+
+    assert_eq!(n, "<div><span id=\"inner\"></span></div>");
+}
+
+#[test]
+fn escaped() {
+    let attrs = [("title", "\"><script>bad()</script>")];
+    let n = render!(div!(..attrs));
+
+    assert_eq!(
+        n,
+        "<div title=\"&quot;&gt;&lt;script&gt;bad()&lt;/script&gt;\"></div>"
+    );
+}
+
+#[test]
+fn invalid_names() {
+    let attrs = [
+        ("", "1"),
+        ("a b", "2"),
+        ("a=b", "3"),
+        ("a\"", "4"),
+        ("a>", "5"),
+        ("a/", "6"),
+        ("a\u{0}", "7"),
+        ("ok", "8"),
+    ];
+
+    let n = render!(div!(..attrs));
+
+    assert_eq!(n, "<div ok=\"8\"></div>");
+}
+
+#[test]
+fn url() {
+    let attrs = [("href", "javascript:alert(1)"), ("HREF", "/a b")];
+    let n = render!(a!(..attrs));
+
+    assert_eq!(n, "<a href=\"about:invalid\" HREF=\"/a%20b\"></a>");
+}
+
+#[test]
+fn collection() {
+    let mut attrs = Attributes::new();
+    attrs.insert("id", "a");
+    attrs.insert("class", "b");
+    attrs.insert("id", String::from("c"));
+
+    assert_eq!(attrs.len(), 2);
+    assert_eq!(attrs.get("id"), Some("c"));
+    assert_eq!(render!(div!(..&attrs)), "<div id=\"c\" class=\"b\"></div>");
+
+    assert_eq!(attrs.remove("id").as_deref(), Some("c"));
+    assert_eq!(render!(div!(..attrs)), "<div class=\"b\"></div>");
+}
+
+#[test]
+fn collect() {
+    let attrs: Attributes = (1..=2)
+        .map(|i| (format!("data-{}", i), i.to_string()))
+        .collect();
+
+    assert_eq!(
+        render!(div!(..attrs)),
+        "<div data-1=\"1\" data-2=\"2\"></div>"
+    );
+}
@@ -0,0 +1,104 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::{internal::Buffer, *};
+
+#[test]
+fn fmt() {
+    let mut s = String::new();
+    render_fmt!(s, div!("laby")).unwrap();
+    assert_eq!(s, "<div>laby</div>");
+}
+
+#[test]
+fn fmt_multiple() {
+    let mut s = String::new();
+    render_fmt!(s, div!(), span!()).unwrap();
+    assert_eq!(s, "<div></div><span></span>");
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn io() {
+    let mut v = Vec::new();
+    render_io!(v, div!("a < b")).unwrap();
+    assert_eq!(v, b"<div>a &lt; b</div>");
+}
+
+#[test]
+fn chunks() {
+    let mut chunks = Vec::new();
+    let n = iter!((0..100).map(|i: u32| li!(i)));
+
+    Buffer::stream(
+        64,
+        &mut |chunk: &str| chunks.push(chunk.to_owned()),
+        |buf| n.render(buf),
+    );
+
+    assert!(chunks.len() > 1);
+    assert!(chunks.iter().all(|chunk| chunk.len() <= 64));
+    assert_eq!(
+        chunks.concat(),
+        render!(iter!((0..100).map(|i: u32| li!(i))))
+    );
+}
+
+#[test]
+fn delimited() {
+    let mut chunks = Vec::new();
+    let v = ["abcd", "", "efg", "", "h"];
+
+    Buffer::stream(4, &mut |chunk: &str| chunks.push(chunk.to_owned()), |buf| {
+        iter!(" ", v).render(buf)
+    });
+
+    assert_eq!(chunks.concat(), "abcd efg h");
+}
+
+#[test]
+fn large_write() {
+    let mut chunks = Vec::new();
+    let s = "x".repeat(100);
+
+    Buffer::stream(
+        16,
+        &mut |chunk: &str| chunks.push(chunk.to_owned()),
+        |buf| div!(s).render(buf),
+    );
+
+    assert_eq!(chunks.concat(), format!("<div>{}</div>", "x".repeat(100)));
+}
+
+#[test]
+fn formatter() {
+    // `fmt::Formatter` is not `Send`
+    struct Page;
+
+    impl std::fmt::Display for Page {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            render_fmt!(*f, div!("a < b"))
+        }
+    }
+
+    assert_eq!(Page.to_string(), "<div>a &lt; b</div>");
+}
+
+#[test]
+fn script_straddle() {
+    let mut chunks = Vec::new();
+    let (b, c) = ("cr", "ipt>");
+
+    // `</s` is flushed before `ipt>` is written
+    Buffer::stream(8, &mut |chunk: &str| chunks.push(chunk.to_owned()), |buf| {
+        script!("xxxxx</s", b, c).render(buf)
+    });
+
+    assert_eq!(chunks.concat(), "<script>xxxxx</scr\\u0069pt></script>");
+    assert_eq!(chunks.concat(), render!(script!("xxxxx</s", b, c)));
+}
@@ -0,0 +1,154 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn only_lits() {
+    let s = styles!(color = "red", width = "100%", margin = 0);
+    assert_eq!(render!(s), "color:red;width:100%;margin:0");
+}
+
+#[test]
+fn only_exprs() {
+    let color = "red";
+    let width = String::from("100%");
+    let s = styles!(color = color, width = width);
+    assert_eq!(render!(s), "color:red;width:100%");
+}
+
+#[test]
+fn nones() {
+    let none: Option<&str> = None;
+    let s = styles!(
+        color = none,
+        width = "1px",
+        margin = none,
+        height = "2px",
+        padding = none
+    );
+    assert_eq!(render!(s), "width:1px;height:2px");
+}
+
+#[test]
+fn only_nones() {
+    let none: Option<&str> = None;
+    let s = styles!(color = none, width = none);
+    assert_eq!(render!(s), "");
+}
+
+#[test]
+fn empty_values() {
+    let empty = "";
+    let s = styles!(color = "", width = empty, height = "1px");
+    assert_eq!(render!(s), "height:1px");
+}
+
+#[test]
+fn escape() {
+    let value = "red;position:fixed\"}<";
+    let s = styles!(color = value, content = "a{b}");
+    assert_eq!(
+        render!(s),
+        r#"color:red\3b position:fixed\22 \7d \3c ;content:a\7b b\7d "#
+    );
+}
+
+#[test]
+fn escape_comment() {
+    let value = "red /*";
+    let s = styles!(color = value, display = "none");
+    assert_eq!(render!(s), r"color:red \2f \2a ;display:none");
+}
+
+#[test]
+fn escape_function() {
+    let (url, expression) = ("URL(//example.com/x.png)", "expression(alert(1))");
+    let s = styles!(background = url, width = expression);
+    assert_eq!(
+        render!(s),
+        r"background:URL\28 \2f \2f example.com\2f x.png);width:expression\28 alert(1))"
+    );
+}
+
+#[test]
+fn escape_function_split() {
+    let value = "(//example.com)";
+    let s = styles!(background = frag!("url", value));
+    assert_eq!(render!(s), r"background:url\28 \2f \2f example.com)");
+}
+
+#[test]
+fn functions() {
+    let value = "rgb(0, 0, 0)";
+    let s = styles!(color = value, width = "calc(1em + 2px)");
+    assert_eq!(render!(s), "color:rgb(0, 0, 0);width:calc(1em + 2px)");
+}
+
+#[test]
+fn underscores() {
+    let s = styles!(
+        font_size = "1em",
+        r#type = "x",
+        border_top_left_radius = "2px"
+    );
+    assert_eq!(
+        render!(s),
+        "font-size:1em;type:x;border-top-left-radius:2px"
+    );
+}
+
+#[test]
+fn custom_properties() {
+    let accent = "#f00";
+    let s = styles!(
+        "--accent" = accent,
+        "--gap" = "4px",
+        color = "var(--accent)"
+    );
+    assert_eq!(render!(s), "--accent:#f00;--gap:4px;color:var(--accent)");
+}
+
+#[test]
+fn conditional() {
+    let bold = true;
+    let italic = false;
+    let s = styles!(font_weight = "bold" => bold, font_style = "italic" => italic, color = "red");
+    assert_eq!(render!(s), "font-weight:bold;color:red");
+}
+
+#[test]
+fn conditional_exprs() {
+    let none: Option<&str> = None;
+    let s = styles!(color = none => true, width = "1px" => false, height = 2 => true);
+    assert_eq!(render!(s), "height:2");
+}
+
+#[test]
+fn attribute() {
+    let color = "red";
+    let width: Option<&str> = None;
+    let n = div!(
+        style = styles!(color = color, width = width, height = "1px"),
+        "&"
+    );
+    assert_eq!(
+        render!(n),
+        "<div style=\"color:red;height:1px\">&amp;</div>"
+    );
+}
+
+#[test]
+fn attribute_escape() {
+    let color = "\"><script>";
+    let n = div!(style = styles!(color = color), title = "\"");
+    assert_eq!(
+        render!(n),
+        r#"<div style="color:\22 \3e \3c script\3e " title="&quot;"></div>"#
+    );
+}
@@ -0,0 +1,160 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::{svg::*, *};
+
+#[test]
+fn namespace() {
+    let n = render!(svg!(viewBox = "0 0 24 24", path!(d = "M0 0h24v24H0z")));
+    assert_eq!(
+        n,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 24 24\"><path d=\"M0 0h24v24H0z\"/></svg>"
+    );
+}
+
+#[test]
+fn explicit_namespace() {
+    let n = render!(svg!(xmlns = "http://www.w3.org/2000/svg", width = 16));
+    assert_eq!(
+        n,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"16\"/>"
+    );
+}
+
+#[test]
+fn self_closing() {
+    let r = 5;
+    let n = render!(circle!(cx = 10, cy = 10, r = r));
+    assert_eq!(n, "<circle cx=\"10\" cy=\"10\" r=\"5\"/>");
+
+    let n = render!(g!());
+    assert_eq!(n, "<g/>");
+}
+
+#[test]
+fn camel_case() {
+    let n = render!(linearGradient!(
+        id = "fade",
+        gradientTransform = "rotate(90)",
+        stop!(offset = "0%", "stop-color" = "#fff"),
+        stop!(offset = "100%"),
+    ));
+
+    assert_eq!(
+        n,
+        "<linearGradient id=\"fade\" gradientTransform=\"rotate(90)\">\
+            <stop offset=\"0%\" stop-color=\"#fff\"/>\
+            <stop offset=\"100%\"/>\
+        </linearGradient>"
+    );
+}
+
+#[test]
+fn children() {
+    let label = "<label>";
+    let n = render!(text!(x = 0, y = 0, label));
+    assert_eq!(n, "<text x=\"0\" y=\"0\">&lt;label&gt;</text>");
+}
+
+#[test]
+fn foreign_object() {
+    let n = render!(foreignObject!(width = 10, height = 10, div!("laby")));
+    assert_eq!(
+        n,
+        "<foreignObject width=\"10\" height=\"10\"><div>laby</div></foreignObject>"
+    );
+}
+
+#[test]
+fn use_element() {
+    let n = render!(r#use!(href = "#icon"));
+    assert_eq!(n, "<use href=\"#icon\"/>");
+}
+
+#[test]
+fn inline() {
+    let n = div!(
+        class = "icon",
+        svg!(
+            viewBox = "0 0 10 10",
+            defs!(clipPath!(id = "c", rect!(width = 10, height = 10))),
+            r#use!(href = "#c"),
+        ),
+    );
+
+    // nested svg markup is flattened into a single static string
+    assert_eq!(core::mem::size_of_val(&n), 0);
+    assert_eq!(
+        render!(n),
+        "<div class=\"icon\">\
+            <svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 10 10\">\
+                <defs><clipPath id=\"c\"><rect width=\"10\" height=\"10\"/></clipPath></defs>\
+                <use href=\"#c\"/>\
+            </svg>\
+        </div>"
+    );
+}
+
+#[test]
+fn qualified() {
+    let n = div!(laby::svg::circle!(r = 1), svg::g!());
+
+    // qualified elements are flattened anywhere
+    assert_eq!(core::mem::size_of_val(&n), 0);
+    assert_eq!(render!(n), "<div><circle r=\"1\"/><g/></div>");
+}
+
+#[test]
+fn script() {
+    let user = "<img src=x onerror=alert(1)>";
+    let n = render!(svg!(script!(user), style!(user)));
+
+    // script and style are not raw text elements in svg
+    assert_eq!(
+        n,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\">\
+            <script>&lt;img src=x onerror=alert(1)&gt;</script>\
+            <style>&lt;img src=x onerror=alert(1)&gt;</style>\
+        </svg>"
+    );
+}
+
+#[test]
+fn script_nested() {
+    let user = "<img src=x onerror=alert(1)>";
+    let s = script!(user);
+    let n = render!(svg!(g!(s)));
+
+    assert_eq!(
+        n,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\">\
+            <g><script>&lt;img src=x onerror=alert(1)&gt;</script></g>\
+        </svg>"
+    );
+}
+
+#[test]
+fn script_foreign_object() {
+    let user = "</script>";
+    let s = script!("'", user, "'");
+    let n = render!(svg!(
+        foreignObject!(script!("'", user, "'"), s),
+        script!(user)
+    ));
+
+    assert_eq!(
+        n,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\">\
+            <foreignObject>\
+                <script>'\\u003C/script>'</script>\
+                <script>'\\u003C/script>'</script>\
+            </foreignObject>\
+            <script>&lt;/script&gt;</script>\
+        </svg>"
+    );
+}
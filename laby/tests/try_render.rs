@@ -0,0 +1,145 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[derive(Debug, PartialEq)]
+struct NotFound(u32);
+
+#[derive(Debug, PartialEq)]
+enum Error {
+    NotFound(u32),
+    Other,
+}
+
+impl From<NotFound> for Error {
+    fn from(NotFound(id): NotFound) -> Self {
+        Self::NotFound(id)
+    }
+}
+
+fn lookup(id: u32) -> Result<&'static str, NotFound> {
+    match id {
+        1 => Ok("one"),
+        2 => Ok("<two>"),
+        _ => Err(NotFound(id)),
+    }
+}
+
+#[test]
+fn result() {
+    assert_eq!(try_render!(lookup(2)), Ok("&lt;two&gt;".into()));
+    assert_eq!(try_render!(lookup(3)), Err(NotFound(3)));
+}
+
+#[test]
+fn children() {
+    let n = div!(class = "user", fallible!(lookup(1)));
+    assert_eq!(try_render!(n), Ok("<div class=\"user\">one</div>".into()));
+
+    let n = div!(class = "user", fallible!(lookup(3)));
+    assert_eq!(try_render!(n), Err(NotFound(3)));
+}
+
+#[test]
+fn nested() {
+    let n = ul!(
+        li!(fallible!(lookup(1))),
+        li!(fallible!(lookup(4))),
+        li!(fallible!(lookup(5))),
+    );
+
+    assert_eq!(try_render!(n), Err(NotFound(4)));
+}
+
+#[test]
+fn nested_node() {
+    let inner = span!(fallible!(lookup(2)));
+    let n = p!("user: ", fallible!(inner));
+
+    assert_eq!(
+        try_render!(n),
+        Ok("<p>user: <span>&lt;two&gt;</span></p>".into())
+    );
+
+    let inner = span!(fallible!(lookup(3)));
+    let n = p!("user: ", fallible!(inner));
+
+    assert_eq!(try_render!(n), Err(NotFound(3)));
+}
+
+#[test]
+fn convert() {
+    let other: Result<&str, Error> = Err(Error::Other);
+    let n = frag!(
+        fallible!(Ok::<_, Error>("a")),
+        fallible!(lookup(6)),
+        fallible!(other)
+    );
+
+    assert_eq!(try_render!(n), Err(Error::NotFound(6)));
+}
+
+#[laby]
+fn user(id: u32) -> impl TryRender<Error = NotFound> {
+    article!(h1!("user"), fallible!(lookup(id)))
+}
+
+#[test]
+fn component() {
+    assert_eq!(
+        try_render!(main!(fallible!(user!(id = 1)))),
+        Ok("<main><article><h1>user</h1>one</article></main>".into())
+    );
+
+    assert_eq!(
+        try_render!(main!(fallible!(user!(id = 7)))),
+        Err(NotFound(7))
+    );
+}
+
+#[test]
+fn multiple() {
+    assert_eq!(
+        try_render!(lookup(1), lookup(2)),
+        Ok("one&lt;two&gt;".into())
+    );
+    assert_eq!(try_render!(lookup(8), lookup(9)), Err(NotFound(8)));
+}
+
+#[test]
+fn size_hint() {
+    let n = div!(fallible!(lookup(1)));
+    assert_eq!(TryRender::size_hint(&n), "<div></div>".len() + "one".len());
+}
+
+#[test]
+fn restore_context() {
+    let mut buf = internal::Buffer::new();
+    let n = div!(script!(fallible!(lookup(3))));
+
+    assert_eq!(n.try_render(&mut buf), Err(NotFound(3)));
+    assert_eq!(buf.context(), internal::EscapeContext::Html);
+}
+
+#[test]
+fn restore_foreign() {
+    let mut buf = internal::Buffer::new();
+    let n = div!(svg!(g!(fallible!(lookup(3)))));
+
+    assert_eq!(n.try_render(&mut buf), Err(NotFound(3)));
+    assert!(!buf.foreign());
+}
+
+#[test]
+fn clear_context() {
+    let mut buf = internal::Buffer::new();
+    buf.set_context(internal::EscapeContext::Script);
+
+    assert_eq!(internal::render_into("<b>", &mut buf), "&lt;b&gt;");
+}
@@ -0,0 +1,161 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn safe() {
+    let s = "https://example.com/path?a=1&b=2#hash";
+    let n = render!(a!(href = s));
+
+    assert_eq!(
+        n,
+        "<a href=\"https://example.com/path?a=1&amp;b=2#hash\"></a>"
+    );
+}
+
+#[test]
+fn relative() {
+    let s = "../page";
+    let n = render!(img!(src = s));
+
+    assert_eq!(n, "<img src=\"../page\">");
+}
+
+#[test]
+fn unsafe_scheme() {
+    for s in [
+        "javascript:alert(1)",
+        "JavaScript:alert(1)",
+        " \u{1}javascript:alert(1)",
+        "java\tscript:alert(1)",
+        "vbscript:msgbox(1)",
+        "data:text/html,<script>alert(1)</script>",
+    ] {
+        let n = render!(a!(href = s));
+        assert_eq!(n, "<a href=\"about:invalid\"></a>");
+    }
+}
+
+#[test]
+fn not_a_scheme() {
+    let s = "path/javascript:alert(1)";
+    let n = render!(a!(href = s));
+
+    assert_eq!(n, "<a href=\"path/javascript:alert(1)\"></a>");
+}
+
+#[test]
+fn encode() {
+    let s = " /a b\"c'd<e>f`g\u{7f}한?x=%20 ";
+    let n = render!(a!(href = s));
+
+    assert_eq!(
+        n,
+        "<a href=\"/a%20b%22c%27d%3Ce%3Ef%60g%7F%ED%95%9C?x=%20\"></a>"
+    );
+}
+
+#[test]
+fn attrs() {
+    let s = "javascript:alert(1)";
+    let n = render!(
+        form!(action = s, button!(formaction = s)),
+        video!(poster = s),
+        blockquote!(cite = s),
+        object!(data = s)
+    );
+
+    assert_eq!(
+        n,
+        "<form action=\"about:invalid\"><button formaction=\"about:invalid\"></button></form>\
+        <video poster=\"about:invalid\"></video>\
+        <blockquote cite=\"about:invalid\"></blockquote>\
+        <object data=\"about:invalid\"></object>"
+    );
+}
+
+#[test]
+fn string_name() {
+    let s = "javascript:alert(1)";
+    let n = render!(a!("HREF" = s));
+
+    assert_eq!(n, "<a HREF=\"about:invalid\"></a>");
+}
+
+#[test]
+fn srcset() {
+    let s = "a.png, b 1.png 2x,javascript:alert(1) 100w";
+    let n = render!(img!(srcset = s));
+
+    assert_eq!(n, "<img srcset=\"a.png, b 1.png 2x, about:invalid 100w\">");
+}
+
+#[test]
+fn format() {
+    let s = "javascript";
+    let n = render!(a!(href = format_args!("{}:alert(1)", s)));
+
+    assert_eq!(n, "<a href=\"about:invalid\"></a>");
+}
+
+#[test]
+fn literal() {
+    let n = render!(a!(href = "/a?b=1"), img!(srcset = "a.png, b.png 2x"));
+    assert_eq!(n, "<a href=\"/a?b=1\"></a><img srcset=\"a.png, b.png 2x\">");
+}
+
+#[test]
+fn raw() {
+    let s = "javascript:void(0)";
+    let n = render!(a!(href = raw!(s)), a!(href = laby::raw!(s)));
+
+    assert_eq!(
+        n,
+        "<a href=\"javascript:void(0)\"></a><a href=\"javascript:void(0)\"></a>"
+    );
+}
+
+#[test]
+fn other_attr() {
+    let s = "javascript:alert(1)";
+    let n = render!(a!(title = s));
+
+    assert_eq!(n, "<a title=\"javascript:alert(1)\"></a>");
+}
+
+#[test]
+fn streaming() {
+    let mut chunks = Vec::new();
+    let s = "javascript:alert(1)";
+
+    internal::Buffer::stream(4, &mut |chunk: &str| chunks.push(chunk.to_owned()), |buf| {
+        a!(href = s).render(buf)
+    });
+
+    assert_eq!(chunks.concat(), "<a href=\"about:invalid\"></a>");
+}
+
+#[test]
+fn limit() {
+    let s = "x".repeat(100);
+    let mut buf = internal::Buffer::new();
+
+    buf.set_max_len(50);
+    a!(href = s).render(&mut buf);
+
+    assert!(buf.error().is_some());
+}
+
+#[test]
+fn explicit() {
+    let s = "javascript:alert(1)";
+    let n = render!(div!(data_src = url!(s)));
+
+    assert_eq!(n, "<div data-src=\"about:invalid\"></div>");
+}
@@ -0,0 +1,87 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn element_attrs() {
+    let n = render!(input!(
+        r#type = "email",
+        name = "email",
+        required = bool!(true)
+    ));
+    assert_eq!(n, "<input type=\"email\" name=\"email\" required>");
+}
+
+#[test]
+fn raw_ident() {
+    let n = render!(label!(r#for = "name", "Name"));
+    assert_eq!(n, "<label for=\"name\">Name</label>");
+}
+
+#[test]
+fn event_handlers() {
+    let n = render!(button!(onclick = "go()", onpointerdown = "go()"));
+    assert_eq!(
+        n,
+        "<button onclick=\"go()\" onpointerdown=\"go()\"></button>"
+    );
+}
+
+#[test]
+fn string_names() {
+    let n = render!(div!("hx-get" = "/items", "@click" = "open = true"));
+    assert_eq!(n, "<div hx-get=\"/items\" @click=\"open = true\"></div>");
+}
+
+#[test]
+fn enumerated() {
+    let n = render!(
+        a!(rel = "noopener NoReferrer", href = "/"),
+        button!(r#type = "Submit")
+    );
+    assert_eq!(
+        n,
+        "<a rel=\"noopener NoReferrer\" href=\"/\"></a><button type=\"Submit\"></button>"
+    );
+}
+
+#[test]
+fn enumerated_dynamic() {
+    // values that are not literals are not checked
+    let kind = "tetx";
+    let n = render!(input!(r#type = kind));
+    assert_eq!(n, "<input type=\"tetx\">");
+}
+
+#[test]
+fn unvalidated_elements() {
+    let n = render!(
+        el!("user-card", username = "chiya"),
+        svg!(viewBox = "0 0 1 1", xmlns = "http://www.w3.org/2000/svg"),
+    );
+
+    assert_eq!(
+        n,
+        "<user-card username=\"chiya\"></user-card><svg viewBox=\"0 0 1 1\" xmlns=\"http://www.w3.org/2000/svg\"/>"
+    );
+}
+
+#[test]
+fn unchecked() {
+    let n = render!(div!(unchecked!(span!(border = 1, align = "left"))));
+    assert_eq!(n, "<div><span border=\"1\" align=\"left\"></span></div>");
+}
+
+#[test]
+#[allow(deprecated)]
+fn unknown_attrs() {
+    // unknown attributes and values are warnings, not errors
+    let n = render!(button!(enabled = true, r#type = "sumbit"));
+    assert_eq!(n, "<button enabled=\"true\" type=\"sumbit\"></button>");
+}
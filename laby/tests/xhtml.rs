@@ -0,0 +1,95 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use laby::*;
+
+#[test]
+fn void() {
+    let n = render_xhtml!(p!("a", br!(), img!(src = "b.png")));
+    assert_eq!(n, "<p>a<br/><img src=\"b.png\"/></p>");
+}
+
+#[test]
+fn boolean() {
+    let n = render_xhtml!(input!(disabled = bool!(true), checked = bool!(false)));
+    assert_eq!(n, "<input disabled=\"disabled\"/>");
+}
+
+#[test]
+fn boolean_variable() {
+    let x = true;
+    let n = render_xhtml!(button!(disabled = bool!(x)));
+    assert_eq!(n, "<button disabled=\"disabled\"></button>");
+}
+
+#[test]
+fn boolean_dynamic_name() {
+    let name = "hidden";
+    let n = render_xhtml!(div!({ name } = bool!(true)));
+    assert_eq!(n, "<div hidden=\"hidden\"></div>");
+}
+
+#[test]
+fn namespace() {
+    let n = render_xhtml!(DocType::XHTML5, html!(body!()));
+    assert_eq!(
+        n,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><!DOCTYPE html>\
+        <html xmlns=\"http://www.w3.org/1999/xhtml\"><body></body></html>"
+    );
+}
+
+#[test]
+fn script() {
+    let code = "a && b < c";
+    let n = render_xhtml!(script!(code));
+    assert_eq!(n, "<script>a &amp;&amp; b &lt; c</script>");
+}
+
+#[test]
+fn invalid_chars() {
+    let s = "a\u{0}b\u{fffe}c\td";
+    let n = render_xhtml!(p!(title = s, s));
+    assert_eq!(
+        n,
+        "<p title=\"a\u{fffd}b\u{fffd}c\td\">a\u{fffd}b\u{fffd}c\td</p>"
+    );
+}
+
+#[test]
+fn invalid_chars_literal() {
+    let n = render_xhtml!(p!("a\u{1b}b"));
+    assert_eq!(n, "<p>a\u{fffd}b</p>");
+}
+
+#[test]
+fn char() {
+    let n = render_xhtml!(p!('\u{1b}', '<'));
+    assert_eq!(n, "<p>\u{fffd}&lt;</p>");
+}
+
+#[test]
+fn style() {
+    let css = "a > b";
+    let n = render_xhtml!(style!("a > b {}", css));
+    assert_eq!(n, "<style>a &gt; b {}a &gt; b</style>");
+}
+
+#[test]
+fn html_unaffected() {
+    let n = || p!(br!(), input!(disabled = bool!(true)), script!("a && b"));
+
+    assert_eq!(
+        render!(n()),
+        "<p><br><input disabled><script>a && b</script></p>"
+    );
+    assert_eq!(
+        render_xhtml!(n()),
+        "<p><br/><input disabled=\"disabled\"/><script>a &amp;&amp; b</script></p>"
+    );
+}
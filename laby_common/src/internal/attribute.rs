@@ -0,0 +1,48 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use super::Buffer;
+use crate::Render;
+
+/// Renders a boolean attribute with the given name, preceded by a space.
+///
+/// If the buffer is in [XHTML](Buffer::set_xhtml) mode, the name is also rendered as the value of
+/// the attribute (e.g. `disabled="disabled"`), because XML does not allow attributes without
+/// values.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut buf = Buffer::new();
+/// render_boolean_attr("disabled", &mut buf);
+///
+/// assert_eq!(buf.into_string(), " disabled");
+/// ```
+#[inline]
+pub fn render_boolean_attr<R>(name: R, buf: &mut Buffer)
+where
+    R: Render,
+{
+    buf.push(' ');
+
+    if buf.xhtml() {
+        let mut name_buf = Buffer::new();
+        name_buf.set_context(buf.context());
+        name_buf.set_xhtml(true);
+        name.render(&mut name_buf);
+
+        let name = name_buf.as_str();
+        buf.push_str(name);
+        buf.push_str("=\"");
+        buf.push_str(name);
+        buf.push('"');
+    } else {
+        name.render(buf);
+    }
+}
@@ -35,16 +35,18 @@
 // OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE
 // OR OTHER DEALINGS IN THE SOFTWARE.
 //
+use super::EscapeContext;
 use alloc::{
     alloc::{alloc, dealloc, handle_alloc_error, realloc, Layout},
     boxed::Box,
     string::String,
+    vec::Vec,
 };
 use core::{
     fmt,
     mem::{align_of, ManuallyDrop},
-    ops::{Add, AddAssign},
-    ptr,
+    ops::{Add, AddAssign, Deref},
+    ptr::{self, NonNull},
 };
 
 /// Buffer for rendered contents.
@@ -65,26 +67,46 @@ use core::{
 ///
 /// assert_eq!(buffer.into_string(), "hello, world!");
 /// ```
-pub struct Buffer {
+///
+/// The lifetime parameter is the lifetime of the sink of a [streaming](Self::stream) buffer, and
+/// can be elided in most places (e.g. `&mut Buffer`). Buffers that are not streaming can have any
+/// lifetime, including `'static`.
+pub struct Buffer<'s> {
     data: *mut u8,
     len: usize,
     capacity: usize,
+    sink: Option<&'s mut Sink<'s>>,
+    flushed: usize,
+    flushed_tail: Tail,
+    context: EscapeContext,
+    xhtml: bool,
+    foreign: bool,
+    max_len: usize,
+    error: Option<BufferError>,
 }
 
-impl Buffer {
+impl<'s> Buffer<'s> {
     /// Create an empty buffer.
     #[inline]
-    pub const fn new() -> Buffer {
+    pub const fn new() -> Self {
         Self {
             data: align_of::<u8>() as *mut u8, // dangling pointer
             len: 0,
             capacity: 0,
+            sink: None,
+            flushed: 0,
+            flushed_tail: Tail::EMPTY,
+            context: EscapeContext::Html,
+            xhtml: false,
+            foreign: false,
+            max_len: usize::MAX,
+            error: None,
         }
     }
 
     /// Create an empty buffer with the given capacity.
     #[inline]
-    pub fn with_capacity(n: usize) -> Buffer {
+    pub fn with_capacity(n: usize) -> Self {
         if n == 0 {
             Self::new()
         } else {
@@ -92,10 +114,91 @@ impl Buffer {
                 data: safe_alloc(n),
                 len: 0,
                 capacity: n,
+                sink: None,
+                flushed: 0,
+                flushed_tail: Tail::EMPTY,
+                context: EscapeContext::Html,
+                xhtml: false,
+                foreign: false,
+                max_len: usize::MAX,
+                error: None,
             }
         }
     }
 
+    /// Create an empty buffer with capacity for the given [size hint](crate::Render::size_hint),
+    /// or 16384 bytes if the size hint is zero.
+    ///
+    /// A size hint of zero usually means that the size is unknown, such as for values whose
+    /// types do not implement [`size_hint`](crate::Render::size_hint), rather than that nothing
+    /// is rendered.
+    #[inline]
+    pub fn with_size_hint(size_hint: usize) -> Self {
+        match size_hint {
+            0 => Self::with_capacity(16384),
+            n => Self::with_capacity(n),
+        }
+    }
+
+    /// Creates a streaming buffer with the given capacity and passes it to `f`.
+    ///
+    /// Whenever the buffer runs out of capacity, its contents are flushed into `sink` and the
+    /// buffer is emptied instead of being reallocated, so the memory used by the buffer stays
+    /// bounded by `capacity`. The buffer only grows when a single write does not fit into an empty
+    /// buffer. Any contents remaining after `f` returns are flushed before this function returns.
+    ///
+    /// Because flushed contents are no longer part of the buffer, [`as_str`](Self::as_str) and
+    /// [`len`](Self::len) only reflect the contents written since the last flush.
+    ///
+    /// The sink is borrowed for the duration of this function only. The buffer passed to `f`
+    /// cannot outlive it, and it is not [`Send`], so the sink does not have to be either.
+    ///
+    /// Consider using the [`render_fmt`](super::render_fmt) or `render_io` functions instead of
+    /// calling this function directly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use laby_common::internal::*;
+    /// let mut chunks = Vec::new();
+    ///
+    /// Buffer::stream(8, &mut |chunk: &str| chunks.push(chunk.to_owned()), |buffer| {
+    ///     buffer.push_str("hello, ");
+    ///     buffer.push_str("world!");
+    /// });
+    ///
+    /// assert_eq!(chunks, ["hello, ", "world!"]);
+    /// ```
+    ///
+    /// The buffer cannot be moved out of `f`.
+    ///
+    /// ```compile_fail
+    /// # use laby_common::internal::*;
+    /// let mut escaped = Buffer::new();
+    ///
+    /// Buffer::stream(8, &mut |_: &str| {}, |buffer| {
+    ///     std::mem::swap(buffer, &mut escaped);
+    /// });
+    /// ```
+    pub fn stream<S, F, R>(capacity: usize, sink: &mut S, f: F) -> R
+    where
+        S: FnMut(&str),
+        F: FnOnce(&mut Buffer<'_>) -> R,
+    {
+        let mut sink = Sink { write: sink };
+
+        let mut buffer = Buffer::with_capacity(capacity);
+        buffer.sink = Some(&mut sink);
+
+        let result = f(&mut buffer);
+
+        if buffer.error.is_none() {
+            buffer.flush_sink();
+        }
+
+        result
+    }
+
     /// Extracts a string slice containing the contents of the buffer.
     #[inline]
     pub fn as_str(&self) -> &str {
@@ -105,6 +208,12 @@ impl Buffer {
         }
     }
 
+    /// Extracts a byte slice containing the contents of the buffer.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
+
     /// Returns an unsafe mutable pointer to the inner data.
     #[inline]
     pub fn as_mut_ptr(&self) -> *mut u8 {
@@ -150,6 +259,10 @@ impl Buffer {
     /// Ensures that the capacity of this buffer is at least `additional` bytes larger than its
     /// length.
     ///
+    /// Because the caller may write into the reserved capacity directly, this grows the buffer
+    /// even if it is in the [failed state](Self::set_max_len) and the maximum length is exceeded.
+    /// Use [`try_reserve`](Self::try_reserve) to respect the maximum length instead.
+    ///
     /// # Panics
     ///
     /// Panics if `size` overflows `isize::MAX`.
@@ -177,18 +290,325 @@ impl Buffer {
         self.reserve_internal(size);
     }
 
-    /// Truncates this buffer, removing all contents.
+    /// Tries to ensure that the capacity of this buffer is at least `size` bytes larger than its
+    /// length, returning an error instead of aborting if memory cannot be allocated.
+    ///
+    /// An error is also returned if writing `size` more bytes would exceed the
+    /// [maximum length](Self::set_max_len) of this buffer. Unlike [`reserve`](Self::reserve),
+    /// a failed reservation does not put this buffer into the failed state, so the caller can
+    /// decide how to proceed. Reservations that require growing always fail if this buffer is
+    /// already in the failed state.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use laby_common::internal::*;
+    /// let mut buffer = Buffer::new();
+    /// buffer.set_max_len(16);
+    ///
+    /// assert_eq!(buffer.try_reserve(8), Ok(()));
+    /// assert_eq!(buffer.try_reserve(32), Err(BufferError::LimitExceeded { max_len: 16 }));
+    /// ```
+    #[inline]
+    pub fn try_reserve(&mut self, size: usize) -> Result<(), BufferError> {
+        if size <= self.capacity - self.len {
+            return Ok(());
+        }
+
+        self.try_reserve_internal(size)
+    }
+
+    /// Returns the maximum number of bytes that can be written to this buffer.
+    ///
+    /// See [`set_max_len`](Self::set_max_len) for more details.
+    #[inline]
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Sets the maximum number of bytes that can be written to this buffer, including the contents
+    /// that were flushed into the sink of a [streaming](Self::stream) buffer. There is no limit by
+    /// default.
+    ///
+    /// This bounds the memory used when rendering untrusted data. Once a write would exceed the
+    /// limit or memory cannot be allocated, the buffer enters a failed state instead of aborting
+    /// the process: the error is recorded, the contents written before are kept, and writes that
+    /// do not fit into the remaining capacity are skipped without growing the buffer, so
+    /// rendering can run to completion. The error can be checked using [`error`](Self::error) or
+    /// [`try_into_string`](Self::try_into_string).
+    ///
+    /// Writes through [`push_str`](Self::push_str) and the [`Render`](crate::Render)
+    /// implementations of laby never grow the buffer beyond the limit, but writes that fit into
+    /// the existing capacity are not checked, so some bytes beyond the limit may be written into a
+    /// preallocated buffer. Such buffers are reported as failed as well.
+    ///
+    /// The limit is not enforced by [`reserve`](Self::reserve) and
+    /// [`reserve_small`](Self::reserve_small), which always grow the buffer as requested because
+    /// their callers write into the reserved capacity directly. Implementations of
+    /// [`Render`](crate::Render) that write into the buffer directly should call
+    /// [`try_reserve`](Self::try_reserve) first to respect the limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use laby_common::internal::*;
+    /// let mut buffer = Buffer::new();
+    /// buffer.set_max_len(10);
+    ///
+    /// for _ in 0..100 {
+    ///     buffer.push_str("hello, ");
+    /// }
+    ///
+    /// assert_eq!(buffer.error(), Some(BufferError::LimitExceeded { max_len: 10 }));
+    /// assert!(buffer.capacity() < 100);
+    /// ```
+    #[inline]
+    pub fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = max_len;
+    }
+
+    /// Returns the error that put this buffer into the failed state, if any.
+    ///
+    /// If an error is returned, the contents of this buffer are incomplete and should be
+    /// discarded. See [`set_max_len`](Self::set_max_len) for more details.
+    #[inline]
+    pub fn error(&self) -> Option<BufferError> {
+        match self.error {
+            Some(error) => Some(error),
+            None if self.written() > self.max_len => Some(BufferError::LimitExceeded {
+                max_len: self.max_len,
+            }),
+            None => None,
+        }
+    }
+
+    /// Returns the context in which strings written to this buffer are escaped.
+    ///
+    /// See [`EscapeContext`] for more details.
+    #[inline]
+    pub fn context(&self) -> EscapeContext {
+        self.context
+    }
+
+    /// Sets the context in which strings written to this buffer are escaped, returning the
+    /// previous context.
+    ///
+    /// This only affects the [`escape`](super::escape) function and the types that use it, and
+    /// not the contents written directly using [`push_str`](Self::push_str) and similar
+    /// functions. The previous context should be restored once the raw text element is closed.
+    #[inline]
+    pub fn set_context(&mut self, context: EscapeContext) -> EscapeContext {
+        core::mem::replace(&mut self.context, context)
+    }
+
+    /// Returns `true` if markup written to this buffer is serialized as XHTML.
+    ///
+    /// See [`set_xhtml`](Self::set_xhtml) for more details.
+    #[inline]
+    pub fn xhtml(&self) -> bool {
+        self.xhtml
+    }
+
+    /// Sets whether markup written to this buffer is serialized as XHTML instead of HTML. This is
+    /// disabled by default.
+    ///
+    /// XHTML documents must be well-formed XML, as required by formats like EPUB. Nodes created by
+    /// element macros check this flag when rendered: void elements are closed in the start tag
+    /// (`<br/>`), boolean attributes are given their name as the value (`disabled="disabled"`),
+    /// and `<html>` includes the XHTML namespace. The [`escape`](super::escape) function delegates
+    /// to [`escape_xml`](super::escape_xml) instead, including in the contents of `<script>` and
+    /// `<style>`, which are not raw text in XHTML.
+    ///
+    /// The flag should be set before anything is rendered, and not changed afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use laby_common::internal::*;
+    /// let mut buffer = Buffer::new();
+    /// buffer.set_xhtml(true);
+    ///
+    /// render_boolean_attr("disabled", &mut buffer);
+    /// assert_eq!(buffer.into_string(), " disabled=\"disabled\"");
+    /// ```
+    #[inline]
+    pub fn set_xhtml(&mut self, xhtml: bool) {
+        self.xhtml = xhtml;
+    }
+
+    /// Returns `true` if markup written to this buffer is foreign content, i.e. inside an `<svg>` or
+    /// `<math>` element.
+    ///
+    /// See [`set_foreign`](Self::set_foreign) for more details.
+    #[inline]
+    pub fn foreign(&self) -> bool {
+        self.foreign
+    }
+
+    /// Sets whether markup written to this buffer is foreign content, returning the previous
+    /// value.
+    ///
+    /// `<script>` and `<style>` elements in SVG and MathML are not raw text elements, so their
+    /// contents are parsed like any other text. In foreign content, the [`escape`](super::escape)
+    /// function therefore ignores the [`Script`](EscapeContext::Script) and
+    /// [`Style`](EscapeContext::Style) contexts and escapes strings using HTML entities. The
+    /// element macros of laby set this flag when rendering the children of `<svg>` and `<math>`,
+    /// and clear it when rendering the children of `<foreignObject>`, which contain HTML again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use laby_common::internal::*;
+    /// let mut buffer = Buffer::new();
+    /// buffer.set_context(EscapeContext::Script);
+    ///
+    /// let foreign = buffer.set_foreign(true);
+    /// escape("<img>", &mut buffer);
+    /// buffer.set_foreign(foreign);
+    ///
+    /// assert_eq!(buffer.into_string(), "&lt;img&gt;");
+    /// ```
+    #[inline]
+    pub fn set_foreign(&mut self, foreign: bool) -> bool {
+        core::mem::replace(&mut self.foreign, foreign)
+    }
+
+    /// Returns the total number of bytes written to this buffer, including the contents that were
+    /// flushed into the sink of a [streaming](Self::stream) buffer.
+    ///
+    /// Unlike [`len`](Self::len), this never decreases when the buffer is flushed, so it can be
+    /// used to determine whether anything was written to the buffer between two points.
+    #[inline]
+    pub fn written(&self) -> usize {
+        self.flushed + self.len
+    }
+
+    /// Calls `f` with this buffer, without flushing its contents into the sink of a
+    /// [streaming](Self::stream) buffer until `f` returns.
+    ///
+    /// This allows the contents written by `f` to be inspected and rewritten before they are
+    /// emitted, such as when sanitizing a rendered URL. The buffer grows as needed instead, up to
+    /// its [maximum length](Self::set_max_len).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use laby_common::internal::*;
+    /// let mut chunks = Vec::new();
+    ///
+    /// Buffer::stream(4, &mut |chunk: &str| chunks.push(chunk.to_owned()), |buf| {
+    ///     buf.hold(|buf| {
+    ///         let start = buf.len();
+    ///         buf.push_str("hello, world");
+    ///
+    ///         let upper = buf.as_str()[start..].to_uppercase();
+    ///         buf.truncate(start);
+    ///         buf.push_str(&upper);
+    ///     });
+    /// });
+    ///
+    /// assert_eq!(chunks, ["HELLO, WORLD"]);
+    /// ```
+    #[inline]
+    pub fn hold<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let sink = self.sink.take();
+        let value = f(self);
+
+        self.sink = sink;
+        value
+    }
+
+    /// Shortens this buffer to `new_len` bytes, removing the rest of the contents.
+    ///
+    /// If `new_len` is greater than or equal to the current length, this has no effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a [`char`] boundary.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len {
+            assert!(self.as_str().is_char_boundary(new_len));
+            self.len = new_len;
+        }
+    }
+
+    /// Truncates this buffer, removing all contents, and restores the default
+    /// [escaping context](Self::context) outside of [foreign content](Self::set_foreign).
     #[inline]
     pub fn clear(&mut self) {
         self.len = 0;
+        self.context = EscapeContext::Html;
+        self.foreign = false;
+    }
+
+    /// Shrinks the capacity of this buffer to the given capacity, or to the length of this
+    /// buffer if it is larger.
+    ///
+    /// If the current capacity is less than or equal to the given capacity, this does nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use laby_common::internal::*;
+    /// let mut buffer = Buffer::with_capacity(100);
+    /// buffer.push_str("hello");
+    ///
+    /// buffer.shrink_to(10);
+    /// assert_eq!(buffer.capacity(), 10);
+    ///
+    /// buffer.shrink_to(0);
+    /// assert_eq!(buffer.capacity(), 5);
+    /// ```
+    pub fn shrink_to(&mut self, capacity: usize) {
+        let new_capacity = core::cmp::max(self.len, capacity);
+
+        if new_capacity >= self.capacity {
+            return;
+        }
+
+        if new_capacity == 0 {
+            // SAFETY: `self.capacity > 0`, and is the same value used to allocate `self.data`.
+            unsafe {
+                dealloc(
+                    self.data,
+                    Layout::from_size_align_unchecked(self.capacity, 1),
+                )
+            };
+            self.data = NonNull::dangling().as_ptr();
+        } else {
+            // SAFETY: `self.capacity > 0`, and is the same value used to allocate `self.data`.
+            self.data = unsafe { safe_realloc(self.data, self.capacity, new_capacity) };
+        }
+
+        self.capacity = new_capacity;
+    }
+
+    /// Removes all contents of this buffer and restores its initial state, keeping its capacity.
+    #[inline]
+    pub(super) fn reset(&mut self) {
+        self.len = 0;
+        self.flushed = 0;
+        self.flushed_tail = Tail::EMPTY;
+        self.sink = None;
+        self.context = EscapeContext::Html;
+        self.xhtml = false;
+        self.foreign = false;
+        self.max_len = usize::MAX;
+        self.error = None;
     }
 
     /// Converts this buffer into [`String`].
     ///
     /// This is a cheap operation that does not involve any copying or allocation.
+    ///
+    /// The buffer must not be in the failed state, in which its contents are incomplete. Use
+    /// [`try_into_string`](Self::try_into_string) to check for errors instead.
     #[inline]
-    pub fn into_string(self) -> String {
+    pub fn into_string(mut self) -> String {
         debug_assert!(self.len <= self.capacity);
+        debug_assert!(self.error.is_none(), "buffer is in the failed state");
+        self.sink = None;
         let buf = ManuallyDrop::new(self);
 
         // SAFETY: This operations satisfy all requirements specified in
@@ -196,17 +616,39 @@ impl Buffer {
         unsafe { String::from_raw_parts(buf.data, buf.len, buf.capacity) }
     }
 
+    /// Converts this buffer into a byte vector.
+    ///
+    /// This is a cheap operation that does not involve any copying or allocation.
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.into_string().into_bytes()
+    }
+
+    /// Converts this buffer into [`String`], or returns the error that put this buffer into the
+    /// failed state.
+    ///
+    /// See [`set_max_len`](Self::set_max_len) for more details.
+    #[inline]
+    pub fn try_into_string(self) -> Result<String, BufferError> {
+        match self.error() {
+            Some(error) => Err(error),
+            None => Ok(self.into_string()),
+        }
+    }
+
     /// Appends the given string slice to the end of this buffer.
+    ///
+    /// If the string does not fit and this buffer cannot grow, the buffer enters the failed state
+    /// and the string is skipped. See [`set_max_len`](Self::set_max_len) for more details.
     #[inline]
     pub fn push_str(&mut self, data: &str) {
         let size = data.len();
 
-        unsafe {
-            // SAFETY: this operation won't overflow because slice cannot exceeds
-            // isize::MAX bytes.
-            // https://doc.rust-lang.org/reference/behavior-considered-undefined.html
-            self.reserve_small(size);
+        if size > self.capacity - self.len && !self.grow(size) {
+            return;
+        }
 
+        unsafe {
             let p = self.data.add(self.len);
             core::ptr::copy_nonoverlapping(data.as_ptr(), p, size);
             self.len += size;
@@ -217,26 +659,165 @@ impl Buffer {
     /// Appends the given `char` to the end of this buffer.
     #[inline]
     pub fn push(&mut self, data: char) {
-        // Question: Is it safe to pass uninitialized memory to `encode_utf8` function?
-        unsafe {
-            self.reserve_small(4);
-            let bp = self.data.add(self.len) as *mut [u8; 4];
-            let result = data.encode_utf8(&mut *bp);
-            self.len += result.len();
+        self.push_str(data.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Grows this buffer for a write of `size` bytes, or puts it into the failed state and returns
+    /// `false` if it cannot grow.
+    #[cold]
+    #[inline(never)]
+    fn grow(&mut self, size: usize) -> bool {
+        match self.try_reserve_internal(size) {
+            Ok(()) => true,
+            Err(error) => {
+                self.error.get_or_insert(error);
+                false
+            }
         }
     }
 
-    #[inline]
+    /// Grows this buffer for [`reserve`](Self::reserve), which must not fail even if this buffer
+    /// is in the failed state.
+    #[cold]
+    #[inline(never)]
     fn reserve_internal(&mut self, size: usize) {
         debug_assert!(size <= core::isize::MAX as usize);
 
+        if self.grow(size) {
+            return;
+        }
+
+        // the caller writes into the reserved capacity directly, so it cannot be skipped
+        let new_capacity = self.len + size;
+
+        // SAFETY: `self.capacity` is the same value used to allocate `self.data` if it is nonzero.
+        self.data = unsafe { safe_realloc(self.data, self.capacity, new_capacity) };
+        self.capacity = new_capacity;
+    }
+
+    /// Tries to grow this buffer for a write of `size` bytes, failing without allocating if the
+    /// write would exceed the maximum length or if this buffer is in the failed state.
+    fn try_reserve_internal(&mut self, size: usize) -> Result<(), BufferError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        if size > isize::MAX as usize {
+            return Err(BufferError::CapacityOverflow);
+        }
+
+        if size > self.max_len.saturating_sub(self.written()) {
+            return Err(BufferError::LimitExceeded {
+                max_len: self.max_len,
+            });
+        }
+
+        if self.sink.is_some() && self.flush_sink() && size <= self.capacity {
+            return Ok(());
+        }
+
+        // grow up to the limit, which the write is known to fit into
         let new_capacity = core::cmp::max(self.capacity * 2, self.capacity + size);
+        let new_capacity = core::cmp::min(new_capacity, self.max_len - self.flushed);
+
         debug_assert!(new_capacity > self.capacity);
-        self.data = unsafe { safe_realloc(self.data, self.capacity, new_capacity) };
+        self.data = unsafe { try_realloc(self.data, self.capacity, new_capacity)? };
         self.capacity = new_capacity;
 
         debug_assert!(!self.data.is_null());
         debug_assert!(self.len <= self.capacity);
+        Ok(())
+    }
+
+    /// Flushes the contents of this buffer into its sink, returning `true` if the buffer was
+    /// emptied.
+    #[cold]
+    #[inline(never)]
+    fn flush_sink(&mut self) -> bool {
+        match self.sink {
+            Some(ref mut sink) if self.len != 0 => {
+                // SAFETY: `self.data` points to `self.len` bytes of valid utf-8.
+                let contents = unsafe {
+                    core::str::from_utf8_unchecked(core::slice::from_raw_parts(self.data, self.len))
+                };
+
+                (sink.write)(contents);
+                self.flushed_tail.record(contents.as_bytes());
+                self.flushed += self.len;
+                self.len = 0;
+                true
+            }
+
+            _ => false,
+        }
+    }
+
+    /// Moves the contents of this buffer into a new buffer, as if they were flushed into a sink.
+    ///
+    /// Unlike [`mem::take`](core::mem::take), this keeps the state of this buffer, so that
+    /// subsequent writes are escaped and limited as if the contents were still there.
+    pub(crate) fn take_flushed(&mut self) -> Buffer<'static> {
+        let mut chunk = Buffer::new();
+        chunk.data = self.data;
+        chunk.len = self.len;
+        chunk.capacity = self.capacity;
+
+        self.flushed_tail.record(chunk.as_bytes());
+        self.flushed += self.len;
+        self.data = NonNull::dangling().as_ptr();
+        self.len = 0;
+        self.capacity = 0;
+        chunk
+    }
+
+    /// Returns up to [`TAIL_LEN`] of the last bytes written to this buffer, including the contents
+    /// that were flushed into the sink of a [streaming](Self::stream) buffer.
+    pub(super) fn tail(&self) -> ([u8; TAIL_LEN], usize) {
+        let mut tail = [0; TAIL_LEN];
+        let contents = self.as_str().as_bytes();
+        let n = core::cmp::min(contents.len(), TAIL_LEN);
+
+        // bytes before the contents of the buffer were flushed
+        let flushed = &self.flushed_tail;
+        let m = core::cmp::min(flushed.len, TAIL_LEN - n);
+
+        tail[..m].copy_from_slice(&flushed.bytes[flushed.len - m..flushed.len]);
+        tail[m..m + n].copy_from_slice(&contents[contents.len() - n..]);
+        (tail, m + n)
+    }
+}
+
+/// Number of bytes returned by [`Buffer::tail`], enough for `</script` and `expression`.
+pub(super) const TAIL_LEN: usize = 10;
+
+/// Sink of a [streaming](Buffer::stream) buffer, borrowed for the duration of the stream.
+struct Sink<'s> {
+    write: &'s mut dyn FnMut(&str),
+}
+
+/// Last bytes flushed out of a buffer.
+#[derive(Clone, Copy)]
+struct Tail {
+    bytes: [u8; TAIL_LEN],
+    len: usize,
+}
+
+impl Tail {
+    const EMPTY: Self = Self {
+        bytes: [0; TAIL_LEN],
+        len: 0,
+    };
+
+    fn record(&mut self, data: &[u8]) {
+        if data.len() >= TAIL_LEN {
+            self.bytes.copy_from_slice(&data[data.len() - TAIL_LEN..]);
+            self.len = TAIL_LEN;
+        } else {
+            let keep = core::cmp::min(self.len, TAIL_LEN - data.len());
+            self.bytes.copy_within(self.len - keep..self.len, 0);
+            self.bytes[keep..keep + data.len()].copy_from_slice(data);
+            self.len = keep + data.len();
+        }
     }
 }
 
@@ -267,11 +848,32 @@ fn safe_alloc(capacity: usize) -> *mut u8 {
 #[cold]
 #[inline(never)]
 unsafe fn safe_realloc(ptr: *mut u8, capacity: usize, new_capacity: usize) -> *mut u8 {
+    match try_realloc(ptr, capacity, new_capacity) {
+        Ok(data) => data,
+        Err(BufferError::CapacityOverflow) => panic!("capacity is too large"),
+        Err(_) => handle_alloc_error(Layout::from_size_align_unchecked(new_capacity, 1)),
+    }
+}
+
+/// Same as [`safe_realloc`] but returns an error instead of aborting. `ptr` is left untouched on
+/// failure.
+///
+/// # Safety
+///
+/// - if `capacity > 0`, `capacity` is the same value that was used to allocate the block of
+///   memory pointed by `ptr`.
+#[cold]
+#[inline(never)]
+unsafe fn try_realloc(
+    ptr: *mut u8,
+    capacity: usize,
+    new_capacity: usize,
+) -> Result<*mut u8, BufferError> {
     assert!(new_capacity > 0);
-    assert!(
-        new_capacity <= core::isize::MAX as usize,
-        "capacity is too large"
-    );
+
+    if new_capacity > isize::MAX as usize {
+        return Err(BufferError::CapacityOverflow);
+    }
 
     let data = if capacity == 0 {
         let new_layout = Layout::from_size_align_unchecked(new_capacity, 1);
@@ -282,22 +884,68 @@ unsafe fn safe_realloc(ptr: *mut u8, capacity: usize, new_capacity: usize) -> *m
     };
 
     if data.is_null() {
-        handle_alloc_error(Layout::from_size_align_unchecked(new_capacity, 1));
+        Err(BufferError::AllocError { size: new_capacity })
+    } else {
+        Ok(data)
     }
+}
+
+/// Error returned when a [`Buffer`] cannot grow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BufferError {
+    /// The required capacity exceeds `isize::MAX` bytes.
+    CapacityOverflow,
+    /// The allocator failed to allocate the given number of bytes.
+    AllocError {
+        /// Number of bytes that were requested.
+        size: usize,
+    },
+    /// The contents of the buffer exceed its [maximum length](Buffer::set_max_len).
+    LimitExceeded {
+        /// Maximum length of the buffer.
+        max_len: usize,
+    },
+}
 
-    data
+impl fmt::Display for BufferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CapacityOverflow => f.write_str("buffer capacity overflow"),
+            Self::AllocError { size } => write!(f, "failed to allocate {} bytes for buffer", size),
+            Self::LimitExceeded { max_len } => {
+                write!(f, "buffer exceeded the maximum length of {} bytes", max_len)
+            }
+        }
+    }
 }
 
-impl Clone for Buffer {
+#[cfg(feature = "std")]
+impl std::error::Error for BufferError {}
+
+impl Clone for Buffer<'_> {
     fn clone(&self) -> Self {
         unsafe {
             if self.is_empty() {
-                Self::new()
+                let mut buf = Self::new();
+                buf.context = self.context;
+                buf.xhtml = self.xhtml;
+                buf.foreign = self.foreign;
+                buf.max_len = self.max_len;
+                buf.error = self.error;
+                buf
             } else {
                 let buf = Self {
                     data: safe_alloc(self.len),
                     len: self.len,
                     capacity: self.len,
+                    sink: None,
+                    flushed: 0,
+                    flushed_tail: Tail::EMPTY,
+                    context: self.context,
+                    xhtml: self.xhtml,
+                    foreign: self.foreign,
+                    max_len: self.max_len,
+                    error: self.error,
                 };
 
                 ptr::copy_nonoverlapping(self.data, buf.data, self.len);
@@ -307,13 +955,13 @@ impl Clone for Buffer {
     }
 }
 
-impl fmt::Debug for Buffer {
+impl fmt::Debug for Buffer<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.as_str().fmt(f)
     }
 }
 
-impl Drop for Buffer {
+impl Drop for Buffer<'_> {
     fn drop(&mut self) {
         if self.capacity != 0 {
             // SAFETY: when `self.capacity > 0`, `self.capacity` is the same value used for
@@ -326,7 +974,7 @@ impl Drop for Buffer {
     }
 }
 
-impl fmt::Write for Buffer {
+impl fmt::Write for Buffer<'_> {
     #[inline]
     fn write_str(&mut self, s: &str) -> fmt::Result {
         Buffer::push_str(self, s);
@@ -334,25 +982,63 @@ impl fmt::Write for Buffer {
     }
 }
 
-impl From<String> for Buffer {
+impl From<String> for Buffer<'_> {
     /// Shrink the data and pass raw pointer directory to buffer
     ///
     /// This operation is `O(1)`
     #[inline]
-    fn from(other: String) -> Buffer {
+    fn from(other: String) -> Self {
         let bs = other.into_boxed_str();
         let data = Box::leak(bs);
         Buffer {
             data: data.as_mut_ptr(),
             len: data.len(),
             capacity: data.len(),
+            sink: None,
+            flushed: 0,
+            flushed_tail: Tail::EMPTY,
+            context: EscapeContext::Html,
+            xhtml: false,
+            foreign: false,
+            max_len: usize::MAX,
+            error: None,
         }
     }
 }
 
-impl From<&str> for Buffer {
+impl<'s> From<Buffer<'s>> for String {
+    /// This operation is `O(1)`
+    #[inline]
+    fn from(other: Buffer<'s>) -> String {
+        debug_assert!(other.error.is_none(), "buffer is in the failed state");
+        other.into_string()
+    }
+}
+
+impl<'s> From<Buffer<'s>> for Vec<u8> {
+    /// This operation is `O(1)`
+    #[inline]
+    fn from(other: Buffer<'s>) -> Vec<u8> {
+        debug_assert!(other.error.is_none(), "buffer is in the failed state");
+        other.into_bytes()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<'s> From<Buffer<'s>> for bytes::Bytes {
+    /// Pass the allocation of the buffer to [`Bytes`](bytes::Bytes) without copying
+    ///
+    /// This operation is `O(1)`
     #[inline]
-    fn from(other: &str) -> Buffer {
+    fn from(other: Buffer<'s>) -> bytes::Bytes {
+        debug_assert!(other.error.is_none(), "buffer is in the failed state");
+        other.into_bytes().into()
+    }
+}
+
+impl From<&str> for Buffer<'_> {
+    #[inline]
+    fn from(other: &str) -> Self {
         let mut buf = Buffer::with_capacity(other.len());
 
         if !other.is_empty() {
@@ -368,29 +1054,55 @@ impl From<&str> for Buffer {
     }
 }
 
-impl Add<&str> for Buffer {
-    type Output = Buffer;
+impl<'s> Add<&str> for Buffer<'s> {
+    type Output = Self;
 
     #[inline]
-    fn add(mut self, other: &str) -> Buffer {
+    fn add(mut self, other: &str) -> Self {
         self.push_str(other);
         self
     }
 }
 
-impl AddAssign<&str> for Buffer {
+impl AddAssign<&str> for Buffer<'_> {
     #[inline]
     fn add_assign(&mut self, other: &str) {
         self.push_str(other)
     }
 }
 
-impl Default for Buffer {
+impl Deref for Buffer<'_> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Buffer<'_> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for Buffer<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Default for Buffer<'_> {
     #[inline]
-    fn default() -> Buffer {
+    fn default() -> Self {
         Buffer::new()
     }
 }
 
-unsafe impl Send for Buffer {}
-unsafe impl Sync for Buffer {}
+// SAFETY: buffers own their data. Streaming buffers borrow a sink that is not necessarily `Send`,
+// but they are never `'static`, because `Buffer::stream` only lends them to a closure that must
+// accept any lifetime.
+unsafe impl Send for Buffer<'static> {}
+unsafe impl Sync for Buffer<'static> {}
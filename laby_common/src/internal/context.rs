@@ -0,0 +1,335 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use super::Buffer;
+
+/// Context in which strings written to a [`Buffer`] are escaped.
+///
+/// The contents of [`<script>`][1] and [`<style>`][2] elements are not parsed as HTML, so escaping
+/// them using HTML entities would corrupt them (e.g. `a && b` would become `a &amp;&amp; b`).
+/// Instead, only the sequences that would prematurely terminate these elements are escaped. The
+/// element macros of laby switch the context of the buffer automatically when rendering the
+/// children of these elements.
+///
+/// The context of a buffer can be changed using [`Buffer::set_context`], and is respected by the
+/// [`escape`](super::escape) function.
+///
+/// [1]: https://developer.mozilla.org/en-US/docs/Web/HTML/Element/script
+/// [2]: https://developer.mozilla.org/en-US/docs/Web/HTML/Element/style
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EscapeContext {
+    /// HTML text or attribute value. The characters `&"'<>` are replaced with the respective HTML
+    /// entities.
+    #[default]
+    Html,
+
+    /// Contents of a `<script>` element. See [`escape_script`].
+    ///
+    /// Strings rendered in this context are escaped as the contents of a JavaScript string
+    /// literal, so they must be placed inside one (e.g. `script!("let name = \"", name, "\";")`).
+    /// String literals written directly in the element macros are code and are only escaped
+    /// using [`escape_script_code`].
+    Script,
+
+    /// Contents of a `<style>` element. See [`escape_style`].
+    Style,
+
+    /// Value of a CSS declaration in a `style` attribute. See [`escape_css_value`].
+    CssValue,
+
+    /// Value of a URL attribute. Strings are written verbatim, so that the complete value can be
+    /// sanitized using [`escape_url`](super::escape_url) afterwards. The buffer must not be used
+    /// as HTML directly.
+    Url,
+}
+
+/// Escapes a string for inclusion in a JavaScript string literal in the contents of a `<script>`
+/// element.
+///
+/// The characters `"`, `'`, `` ` ``, `$` and `\` are escaped with a backslash, line feed and
+/// carriage return are escaped as `\n` and `\r`, `<` is escaped as `\u003C`, and the line
+/// terminators U+2028 and U+2029 are escaped as `\u2028` and `\u2029`. All other characters are
+/// written verbatim. The string can therefore neither terminate the literal it is placed in,
+/// whether it is quoted using `"`, `'` or `` ` ``, nor start a substitution in a template literal,
+/// nor close the `<script>` element.
+///
+/// If `</script` or `<!--` is split between the end of the buffer and the start of the string,
+/// the first character of the string is escaped as a `\u` escape sequence as well.
+///
+/// The string must be placed inside a string literal or template literal. Code and serialized
+/// JSON can be written using [`escape_script_code`] instead.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut buf = Buffer::new();
+/// escape_script("\"; alert('</script>') //", &mut buf);
+///
+/// assert_eq!(buf.into_string(), r#"\"; alert(\'\u003C/script>\') //"#);
+/// ```
+pub fn escape_script(feed: &str, buf: &mut Buffer) {
+    let feed = escape_straddle(feed, buf, SCRIPT_PATTERNS, true);
+    let bytes = feed.as_bytes();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let (escaped, len) = match bytes[i] {
+            b'"' => ("\\\"", 1),
+            b'\'' => ("\\'", 1),
+            b'`' => ("\\`", 1),
+            b'$' => ("\\$", 1),
+            b'\\' => ("\\\\", 1),
+            b'\n' => ("\\n", 1),
+            b'\r' => ("\\r", 1),
+            b'<' => ("\\u003C", 1),
+
+            // U+2028 and U+2029 are encoded as E2 80 A8 and E2 80 A9
+            0xe2 if bytes[i + 1] == 0x80 && bytes[i + 2] == 0xa8 => ("\\u2028", 3),
+            0xe2 if bytes[i + 1] == 0x80 && bytes[i + 2] == 0xa9 => ("\\u2029", 3),
+
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+
+        buf.push_str(&feed[start..i]);
+        buf.push_str(escaped);
+        i += len;
+        start = i;
+    }
+
+    buf.push_str(&feed[start..]);
+}
+
+/// Escapes JavaScript code for inclusion in the contents of a `<script>` element.
+///
+/// The sequences `</script` (case-insensitive) and `<!--` are escaped as `<\/script` and
+/// `\u003C!--` respectively, and the line terminators U+2028 and U+2029 are escaped as `\u2028`
+/// and `\u2029`. These escape sequences are valid in JavaScript string literals, template literals
+/// and regular expressions, as well as in JSON strings. All other characters are written verbatim.
+///
+/// If one of these sequences is split between the end of the buffer and the start of the string,
+/// the first character of the string is escaped as a `\u` escape sequence instead.
+///
+/// Outside of literals, these escape sequences are syntax errors, and there is no other way to
+/// escape `<!--` and `</script` without changing the meaning of the script. The code must
+/// therefore be trusted. The element macros of laby write the string literals in a `<script>`
+/// element using this function, and all other values using [`escape_script`].
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut buf = Buffer::new();
+/// escape_script_code("a && b < '</script>'", &mut buf);
+///
+/// assert_eq!(buf.into_string(), r"a && b < '<\/script>'");
+/// ```
+pub fn escape_script_code(feed: &str, buf: &mut Buffer) {
+    escape_raw_text(feed, buf, SCRIPT_PATTERNS, true);
+}
+
+/// Escapes a string for inclusion in the contents of a `<style>` element.
+///
+/// The sequence `</style` (case-insensitive) is escaped as `<\/style`, which is valid in CSS
+/// strings and identifiers. All other characters are written verbatim.
+///
+/// If the sequence is split between the end of the buffer and the start of the string, the first
+/// character of the string is escaped as a hexadecimal CSS escape sequence instead.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut buf = Buffer::new();
+/// escape_style("a > b::after { content: '</style>' }", &mut buf);
+///
+/// assert_eq!(buf.into_string(), r"a > b::after { content: '<\/style>' }");
+/// ```
+pub fn escape_style(feed: &str, buf: &mut Buffer) {
+    escape_raw_text(feed, buf, &[b"</style"], false);
+}
+
+/// Escapes a string for inclusion as the value of a CSS declaration in a `style` attribute.
+///
+/// The characters `"&'*/\;<>{}` and control characters are replaced with hexadecimal CSS escape
+/// sequences followed by a space (e.g. `;` becomes `\3b `), so that the value can neither
+/// terminate the declaration nor the attribute, nor open a comment that would swallow the
+/// declarations following it. All other characters are written verbatim, so values like
+/// `rgb(0, 0, 0)` and `calc(1em + 2px)` are unaffected.
+///
+/// Functions that load external resources or run code are rejected by escaping their opening
+/// parenthesis instead, so `url(x)` and `expression(x)` (case-insensitive) become the identifiers
+/// `url\28 x)` and `expression\28 x)` and are ignored by the browser. Other functions are not
+/// affected. Sanitized URLs can be written using the `raw!` macro of laby instead.
+///
+/// Because quotes are escaped, CSS strings cannot be written using this function; they are
+/// rendered as identifiers instead. Likewise, `/` and `*` cannot be used as operators, e.g. in
+/// `calc(1em * 2)` or the `font` shorthand.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut buf = Buffer::new();
+/// escape_css_value("red; background: url(//example.com)", &mut buf);
+///
+/// assert_eq!(buf.into_string(), r"red\3b  background: url\28 \2f \2f example.com)");
+/// ```
+pub fn escape_css_value(feed: &str, buf: &mut Buffer) {
+    let bytes = feed.as_bytes();
+    let mut start = 0;
+
+    for (i, &c) in bytes.iter().enumerate() {
+        let escape = match c {
+            b'"' | b'&' | b'\'' | b'*' | b'/' | b'\\' | b';' | b'<' | b'>' | b'{' | b'}' => true,
+
+            b'(' => {
+                // the name of the function may have been written before this string
+                buf.push_str(&feed[start..i]);
+                start = i;
+                is_unsafe_css_function(buf)
+            }
+
+            _ => c.is_ascii_control(),
+        };
+
+        if escape {
+            buf.push_str(&feed[start..i]);
+            buf.push('\\');
+
+            if c >= 0x10 {
+                buf.push(HEX_DIGITS[(c >> 4) as usize] as char);
+            }
+
+            buf.push(HEX_DIGITS[(c & 0xf) as usize] as char);
+            buf.push(' ');
+            start = i + 1;
+        }
+    }
+
+    buf.push_str(&feed[start..]);
+}
+
+/// Returns `true` if the contents of the buffer end with the name of a CSS function that loads
+/// external resources or runs code.
+fn is_unsafe_css_function(buf: &Buffer) -> bool {
+    let (tail, len) = buf.tail();
+    let tail = &tail[..len];
+
+    [&b"url"[..], b"expression"].iter().any(|name| {
+        tail.len() >= name.len() && tail[tail.len() - name.len()..].eq_ignore_ascii_case(name)
+    })
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Sequences that are escaped in the contents of a `<script>` element.
+const SCRIPT_PATTERNS: &[&[u8]] = &[b"</script", b"<!--"];
+
+fn escape_raw_text(feed: &str, buf: &mut Buffer, patterns: &[&[u8]], script: bool) {
+    let feed = escape_straddle(feed, buf, patterns, script);
+    let bytes = feed.as_bytes();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'<' if bytes[i..].starts_with(b"<!--") && script => {
+                buf.push_str(&feed[start..i]);
+                buf.push_str("\\u003C");
+                start = i + 1;
+            }
+
+            b'<' if patterns
+                .iter()
+                .any(|pattern| starts_with(&bytes[i..], pattern)) =>
+            {
+                buf.push_str(&feed[start..=i]);
+                buf.push('\\');
+                start = i + 1;
+            }
+
+            // U+2028 and U+2029 are encoded as E2 80 A8 and E2 80 A9
+            0xe2 if script && bytes[i + 1] == 0x80 && matches!(bytes[i + 2], 0xa8 | 0xa9) => {
+                buf.push_str(&feed[start..i]);
+                buf.push_str(if bytes[i + 2] == 0xa8 {
+                    "\\u2028"
+                } else {
+                    "\\u2029"
+                });
+
+                i += 3;
+                start = i;
+                continue;
+            }
+
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    buf.push_str(&feed[start..]);
+}
+
+/// Escapes the first characters of `feed` while they complete one of `patterns` started by the
+/// contents of the buffer, and returns the rest of `feed`.
+fn escape_straddle<'a>(
+    mut feed: &'a str,
+    buf: &mut Buffer,
+    patterns: &[&[u8]],
+    script: bool,
+) -> &'a str {
+    while let Some(&first) = feed.as_bytes().first() {
+        // includes the contents flushed into the sink of a streaming buffer
+        let (tail, len) = buf.tail();
+
+        if !patterns
+            .iter()
+            .any(|pattern| straddles(&tail[..len], feed.as_bytes(), pattern))
+        {
+            break;
+        }
+
+        if script {
+            push_hex(buf, "\\u00", first);
+        } else {
+            push_hex(buf, "\\", first);
+            buf.push(' ');
+        }
+
+        // patterns are ascii, so the first character is a single byte
+        feed = &feed[1..];
+    }
+
+    feed
+}
+
+/// Returns `true` if `pattern` starts in `tail` and ends in `head`.
+fn straddles(tail: &[u8], head: &[u8], pattern: &[u8]) -> bool {
+    let tail = &tail[tail.len().saturating_sub(pattern.len() - 1)..];
+
+    tail.iter().enumerate().any(|(i, &c)| {
+        let (left, right) = pattern.split_at(tail.len() - i);
+        c == b'<' && starts_with(&tail[i..], left) && starts_with(head, right)
+    })
+}
+
+fn starts_with(haystack: &[u8], pattern: &[u8]) -> bool {
+    haystack.len() >= pattern.len() && haystack[..pattern.len()].eq_ignore_ascii_case(pattern)
+}
+
+fn push_hex(buf: &mut Buffer, prefix: &str, value: u8) {
+    buf.push_str(prefix);
+    buf.push(HEX_DIGITS[(value >> 4) as usize].to_ascii_uppercase() as char);
+    buf.push(HEX_DIGITS[(value & 0xf) as usize].to_ascii_uppercase() as char);
+}
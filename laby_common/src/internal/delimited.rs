@@ -0,0 +1,50 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use super::Buffer;
+use crate::Render;
+
+/// Renders a value preceded by a delimiter, removing the delimiter again if the value renders
+/// nothing.
+///
+/// Returns `true` if the value rendered anything. This is used to place delimiters only between
+/// non-empty items, such as the class names rendered by `classes!`.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut buf = Buffer::new();
+/// let none: Option<&str> = None;
+///
+/// assert!(render_delimited("a", "", &mut buf));
+/// assert!(!render_delimited(none, " ", &mut buf));
+/// assert!(render_delimited("b", " ", &mut buf));
+///
+/// assert_eq!(buf.into_string(), "a b");
+/// ```
+#[inline]
+pub fn render_delimited<R>(value: R, delimiter: &str, buf: &mut Buffer) -> bool
+where
+    R: Render,
+{
+    buf.push_str(delimiter);
+
+    // compare the total number of bytes written, because the buffer may be flushed while
+    // rendering the value
+    let written = buf.written();
+    value.render(buf);
+
+    if buf.written() == written {
+        // nothing was written after the delimiter, so it is still entirely in the buffer
+        buf.truncate(buf.len() - delimiter.len());
+        false
+    } else {
+        true
+    }
+}
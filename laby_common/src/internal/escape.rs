@@ -49,7 +49,7 @@ static ESCAPE_LUT: [u8; 256] = [
 const ESCAPED: [&str; 5] = ["&quot;", "&amp;", "&#39;", "&lt;", "&gt;"];
 const ESCAPED_LEN: usize = 5;
 
-use super::buffer::Buffer;
+use super::{buffer::Buffer, escape_css_value, escape_script, escape_style, EscapeContext};
 use alloc::string::String;
 use core::ptr;
 use ptr::copy_nonoverlapping as memcpy_16;
@@ -68,6 +68,15 @@ use ptr::copy_nonoverlapping as memcpy_16;
 /// Non-[x86][4] platforms will always use the fallback scalar implementation regardless of SIMD
 /// support.
 ///
+/// If the [context](EscapeContext) of the buffer is not [`Html`](EscapeContext::Html), this
+/// function delegates to [`escape_script`], [`escape_style`] or [`escape_css_value`] instead, or
+/// writes the string verbatim for [`Url`](EscapeContext::Url).
+///
+/// If the buffer is in [XHTML](Buffer::set_xhtml) mode, this function delegates to [`escape_xml`]
+/// in the [`Html`](EscapeContext::Html), [`Script`](EscapeContext::Script) and
+/// [`Style`](EscapeContext::Style) contexts instead, so that the output is also valid XML. In
+/// [foreign content](Buffer::set_foreign), the `Script` and `Style` contexts are ignored as well.
+///
 /// To escape a string as a [`String`] instead of [`Buffer`] conveniently, see [`escape_str`]
 /// function.
 ///
@@ -90,7 +99,83 @@ use ptr::copy_nonoverlapping as memcpy_16;
 /// assert_eq!(buf.into_string(), "a &lt; b");
 /// ```
 pub fn escape(feed: &str, buf: &mut Buffer) {
-    escape_impl(feed, buf)
+    match buf.context() {
+        EscapeContext::Html | EscapeContext::Script | EscapeContext::Style if buf.xhtml() => {
+            escape_xml(feed, buf)
+        }
+        EscapeContext::Html => escape_impl(feed, buf),
+        EscapeContext::Script | EscapeContext::Style if buf.foreign() => escape_impl(feed, buf),
+        EscapeContext::Script => escape_script(feed, buf),
+        EscapeContext::Style => escape_style(feed, buf),
+        EscapeContext::CssValue => escape_css_value(feed, buf),
+        EscapeContext::Url => buf.push_str(feed),
+    }
+}
+
+/// Takes a string slice [`&str`] and writes the escaped form into the given [`Buffer`], replacing
+/// characters that are not allowed in XML 1.0 documents.
+///
+/// This is equivalent to [`escape`] in the [`Html`](EscapeContext::Html) context, except that
+/// control characters other than tab, line feed and carriage return, as well as the noncharacters
+/// U+FFFE and U+FFFF, are replaced with U+FFFD REPLACEMENT CHARACTER. XML parsers reject these
+/// characters even as character references, so they cannot be escaped in any other way.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut buf = Buffer::new();
+/// escape_xml("a < b\u{1b}[0m", &mut buf);
+///
+/// assert_eq!(buf.into_string(), "a &lt; b\u{fffd}[0m");
+/// ```
+pub fn escape_xml(feed: &str, buf: &mut Buffer) {
+    let bytes = feed.as_bytes();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let len = match bytes[i] {
+            b'\t' | b'\n' | b'\r' => 0,
+            c if c < 0x20 => 1,
+            // U+FFFE and U+FFFF
+            0xef if matches!(bytes.get(i + 1..i + 3), Some([0xbf, 0xbe | 0xbf])) => 3,
+            _ => 0,
+        };
+
+        if len == 0 {
+            i += 1;
+            continue;
+        }
+
+        if start < i {
+            escape_impl(&feed[start..i], buf);
+        }
+
+        buf.push('\u{fffd}');
+        i += len;
+        start = i;
+    }
+
+    if start < feed.len() {
+        escape_impl(&feed[start..], buf);
+    }
+}
+
+/// Escapes a string shorter than 16 bytes.
+///
+/// The escaped string is written directly into the buffer if there is room for the longest
+/// possible result, and piece by piece otherwise, so that a buffer close to its maximum length
+/// only fails if the escaped string does not actually fit.
+#[inline]
+unsafe fn escape_small(feed: &str, buf: &mut Buffer) {
+    if buf.try_reserve(feed.len() * 6).is_ok() {
+        let l = naive::escape_small(feed, buf.as_mut_ptr().add(buf.len()));
+        buf.advance(l);
+    } else {
+        let ptr = feed.as_ptr();
+        naive::escape(buf, ptr, ptr, ptr.add(feed.len()));
+    }
 }
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -121,9 +206,7 @@ fn escape_impl(feed: &str, buf: &mut Buffer) {
 
     unsafe {
         if feed.len() < 16 {
-            buf.reserve_small(feed.len() * 6);
-            let l = naive::escape_small(feed, buf.as_mut_ptr().add(buf.len()));
-            buf.advance(l);
+            escape_small(feed, buf);
         } else {
             let fun = FN.load(Ordering::Relaxed);
             core::mem::transmute::<FnRaw, fn(&str, &mut Buffer)>(fun)(feed, buf);
@@ -135,9 +218,7 @@ fn escape_impl(feed: &str, buf: &mut Buffer) {
 fn escape_impl(feed: &str, buf: &mut Buffer) {
     unsafe {
         if feed.len() < 16 {
-            buf.reserve_small(feed.len() * 6);
-            let l = naive::escape_small(feed, buf.as_mut_ptr().add(buf.len()));
-            buf.advance(l);
+            escape_small(feed, buf);
         } else {
             fallback::escape(feed, buf)
         }
@@ -325,7 +406,9 @@ mod naive {
     #[cfg(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64"))]
     #[inline]
     pub unsafe fn push_escaped_str(value: &str, buffer: &mut Buffer) {
-        buffer.reserve_small(value.len());
+        if buffer.try_reserve(value.len()).is_err() {
+            return buffer.push_str(value);
+        }
 
         let src = value.as_ptr();
         let dst = buffer.as_mut_ptr().add(buffer.len());
@@ -10,8 +10,24 @@
 //!
 //! Types provided in this module may be useful when implementing the
 //! [`Render`](crate::render::Render) trait for a custom object.
+mod attribute;
 mod buffer;
+mod context;
+mod delimited;
 mod escape;
+mod name;
+mod pool;
+mod pretty;
+mod stream;
+mod url;
 
+pub use attribute::*;
 pub use buffer::*;
+pub use context::*;
+pub use delimited::*;
 pub use escape::*;
+pub use name::*;
+pub use pool::*;
+pub use pretty::*;
+pub use stream::*;
+pub use url::*;
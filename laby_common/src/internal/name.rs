@@ -0,0 +1,103 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+
+/// Returns `true` if the given string is a valid HTML [attribute name][1].
+///
+/// A valid attribute name is not empty, and does not contain control characters, spaces,
+/// [noncharacters][2], or any of the characters `"'<>/=&`. Writing an attribute name that fails
+/// this check without escaping can break out of the element's start tag.
+///
+/// [1]: https://html.spec.whatwg.org/multipage/syntax.html#attributes-2
+/// [2]: https://infra.spec.whatwg.org/#noncharacter
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// assert!(is_valid_attribute_name("data-id"));
+/// assert!(is_valid_attribute_name("aria-label"));
+/// assert!(is_valid_attribute_name("@click"));
+///
+/// assert!(!is_valid_attribute_name(""));
+/// assert!(!is_valid_attribute_name("a b"));
+/// assert!(!is_valid_attribute_name("onload=alert(1)"));
+/// assert!(!is_valid_attribute_name("x><script>"));
+/// ```
+pub fn is_valid_attribute_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|c| {
+            !c.is_control()
+                && !matches!(c, ' ' | '"' | '\'' | '<' | '>' | '/' | '=' | '&')
+                && !is_noncharacter(c)
+        })
+}
+
+fn is_noncharacter(c: char) -> bool {
+    let c = c as u32;
+    (0xfdd0..=0xfdef).contains(&c) || c & 0xfffe == 0xfffe
+}
+
+/// Returns `true` if the given string is a valid [custom element name][1].
+///
+/// A valid custom element name starts with a lowercase ASCII letter, contains a hyphen, contains
+/// no uppercase ASCII letters, and consists only of the characters allowed by the
+/// `PotentialCustomElementName` grammar. The names reserved by SVG and MathML (e.g.
+/// `font-face` and `annotation-xml`) are not valid.
+///
+/// [1]: https://html.spec.whatwg.org/multipage/custom-elements.html#valid-custom-element-name
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// assert!(is_valid_custom_element_name("user-card"));
+/// assert!(is_valid_custom_element_name("app-shell-2"));
+/// assert!(is_valid_custom_element_name("math-α"));
+///
+/// assert!(!is_valid_custom_element_name("usercard"));
+/// assert!(!is_valid_custom_element_name("User-Card"));
+/// assert!(!is_valid_custom_element_name("1-card"));
+/// assert!(!is_valid_custom_element_name("user card"));
+/// assert!(!is_valid_custom_element_name("font-face"));
+/// ```
+pub fn is_valid_custom_element_name(name: &str) -> bool {
+    const RESERVED_NAMES: [&str; 8] = [
+        "annotation-xml",
+        "color-profile",
+        "font-face",
+        "font-face-src",
+        "font-face-uri",
+        "font-face-format",
+        "font-face-name",
+        "missing-glyph",
+    ];
+
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.contains('-')
+        && name.chars().all(is_custom_element_name_char)
+        && !RESERVED_NAMES.contains(&name)
+}
+
+fn is_custom_element_name_char(c: char) -> bool {
+    matches!(c,
+        '-' | '.' | '0'..='9' | '_' | 'a'..='z' | '\u{b7}'
+        | '\u{c0}'..='\u{d6}'
+        | '\u{d8}'..='\u{f6}'
+        | '\u{f8}'..='\u{37d}'
+        | '\u{37f}'..='\u{1fff}'
+        | '\u{200c}'..='\u{200d}'
+        | '\u{203f}'..='\u{2040}'
+        | '\u{2070}'..='\u{218f}'
+        | '\u{2c00}'..='\u{2fef}'
+        | '\u{3001}'..='\u{d7ff}'
+        | '\u{f900}'..='\u{fdcf}'
+        | '\u{fdf0}'..='\u{fffd}'
+        | '\u{10000}'..='\u{effff}'
+    )
+}
@@ -0,0 +1,183 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use super::Buffer;
+use crate::Render;
+use alloc::vec::Vec;
+
+/// Default maximum number of buffers kept by a [`BufferPool`].
+pub const POOL_MAX_BUFFERS: usize = 16;
+
+/// Default maximum capacity of buffers kept by a [`BufferPool`]. Buffers that grow larger are
+/// shrunk to this capacity when they are returned to the pool.
+pub const POOL_MAX_CAPACITY: usize = 1 << 20;
+
+/// Clears the given buffer and renders the given value into it, returning the rendered contents.
+///
+/// Unlike rendering into a new [`Buffer`], this reuses the memory already allocated by the
+/// buffer, so rendering many values one after another only allocates when a value renders more
+/// than any previous value.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut buf = Buffer::new();
+///
+/// assert_eq!(render_into("a < b", &mut buf), "a &lt; b");
+/// assert_eq!(render_into("c", &mut buf), "c");
+/// ```
+#[inline]
+pub fn render_into<'a, R>(value: R, buf: &'a mut Buffer<'_>) -> &'a str
+where
+    R: Render,
+{
+    buf.clear();
+    value.render(buf);
+    buf.as_str()
+}
+
+/// Pool of [`Buffer`]s that keeps their capacity between renders.
+///
+/// Buffers taken from the pool using [`get`](Self::get) are empty, and can be returned using
+/// [`put`](Self::put) to be reused later. At most [`POOL_MAX_BUFFERS`] buffers are kept by
+/// default, and buffers larger than [`POOL_MAX_CAPACITY`] are shrunk when they are returned, so
+/// that a single large render does not hold on to its memory forever. These limits can be
+/// changed using [`with_limits`](Self::with_limits).
+///
+/// This type is not synchronized. To share a pool between threads, wrap it in a mutex, or use
+/// `with_pooled_buffer`, which requires the `std` feature and keeps a separate pool for each
+/// thread.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut pool = BufferPool::new();
+///
+/// let len = pool.render("a < b", |s| s.len());
+/// assert_eq!(len, 8);
+///
+/// let buf = pool.get();
+/// assert!(buf.is_empty());
+/// assert!(buf.capacity() >= 8);
+/// ```
+#[derive(Debug)]
+pub struct BufferPool {
+    buffers: Vec<Buffer<'static>>,
+    max_buffers: usize,
+    max_capacity: usize,
+}
+
+impl BufferPool {
+    /// Creates an empty pool with the default limits.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with_limits(POOL_MAX_BUFFERS, POOL_MAX_CAPACITY)
+    }
+
+    /// Creates an empty pool that keeps at most `max_buffers` buffers, each with a capacity of at
+    /// most `max_capacity` bytes.
+    #[inline]
+    pub const fn with_limits(max_buffers: usize, max_capacity: usize) -> Self {
+        Self {
+            buffers: Vec::new(),
+            max_buffers,
+            max_capacity,
+        }
+    }
+
+    /// Returns the number of buffers in this pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns `true` if this pool has no buffers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// Takes an empty buffer from this pool, or creates a new one if the pool is empty.
+    #[inline]
+    pub fn get(&mut self) -> Buffer<'static> {
+        self.buffers.pop().unwrap_or_default()
+    }
+
+    /// Returns a buffer to this pool, removing its contents.
+    ///
+    /// The buffer is shrunk if its capacity exceeds the limit of this pool, and dropped if the
+    /// pool is full.
+    pub fn put(&mut self, mut buffer: Buffer<'static>) {
+        if self.buffers.len() >= self.max_buffers {
+            return;
+        }
+
+        buffer.reset();
+        buffer.shrink_to(self.max_capacity);
+        self.buffers.push(buffer);
+    }
+
+    /// Renders the given value into a buffer taken from this pool, and passes the rendered
+    /// contents to `f`. The buffer is returned to this pool afterwards.
+    pub fn render<R, F, T>(&mut self, value: R, f: F) -> T
+    where
+        R: Render,
+        F: FnOnce(&str) -> T,
+    {
+        let mut buffer = self.get();
+        let result = f(render_into(value, &mut buffer));
+
+        self.put(buffer);
+        result
+    }
+}
+
+impl Default for BufferPool {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Passes a buffer taken from a pool local to the current thread to `f`, and returns the buffer
+/// to the pool afterwards.
+///
+/// This function is only available with the `std` feature. The pool has the default limits of
+/// [`BufferPool`]. This function can be called recursively, in which case each call takes a
+/// separate buffer.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let len = with_pooled_buffer(|buf| render_into("a < b", buf).len());
+/// assert_eq!(len, 8);
+/// ```
+#[cfg(feature = "std")]
+pub fn with_pooled_buffer<F, T>(f: F) -> T
+where
+    F: FnOnce(&mut Buffer) -> T,
+{
+    use core::cell::RefCell;
+
+    std::thread_local! {
+        static POOL: RefCell<BufferPool> = const { RefCell::new(BufferPool::new()) };
+    }
+
+    // the pool may already be destroyed if this is called while the thread is exiting
+    let mut buffer = POOL
+        .try_with(|pool| pool.borrow_mut().get())
+        .unwrap_or_default();
+
+    let result = f(&mut buffer);
+    let _ = POOL.try_with(|pool| pool.borrow_mut().put(buffer));
+
+    result
+}
@@ -0,0 +1,405 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use super::Buffer;
+use crate::Render;
+use alloc::{string::String, vec, vec::Vec};
+
+/// String used to indent each level of nesting by [`prettify`].
+pub const PRETTY_INDENT: &str = "  ";
+
+/// Elements that are placed on separate lines by [`prettify`].
+const BLOCK_TAGS: [&str; 58] = [
+    "address",
+    "article",
+    "aside",
+    "base",
+    "blockquote",
+    "body",
+    "caption",
+    "col",
+    "colgroup",
+    "dd",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "li",
+    "link",
+    "main",
+    "menu",
+    "meta",
+    "nav",
+    "noscript",
+    "ol",
+    "optgroup",
+    "option",
+    "p",
+    "pre",
+    "script",
+    "section",
+    "select",
+    "style",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "template",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "ul",
+];
+
+/// Elements whose contents are never reformatted by [`prettify`].
+const VERBATIM_TAGS: [&str; 5] = ["pre", "script", "style", "textarea", "title"];
+
+/// Elements whose contents are not parsed as markup.
+const RAW_TEXT_TAGS: [&str; 4] = ["script", "style", "textarea", "title"];
+
+const VOID_TAGS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Renders the given value and [prettifies](prettify) the result.
+///
+/// This is the function behind the `render_pretty!` macro of laby.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// assert_eq!(render_pretty("a < b"), "a &lt; b");
+/// ```
+pub fn render_pretty<R>(value: R) -> String
+where
+    R: Render,
+{
+    let mut buf = Buffer::with_size_hint(value.size_hint());
+    value.render(&mut buf);
+    prettify(buf.as_str())
+}
+
+/// Reformats HTML for readability, placing block-level elements on separate lines indented by
+/// their depth.
+///
+/// Only whitespace is changed. Block-level elements like `<div>` and `<li>` start on a new line,
+/// and their children are indented using [`PRETTY_INDENT`] if any of them is a block-level
+/// element. Otherwise, the element is kept on a single line. Text and inline elements like
+/// `<span>` and `<b>` are never separated from each other, because whitespace between them is
+/// significant, and the contents of whitespace-sensitive elements like `<pre>` and `<textarea>`,
+/// as well as `<script>` and `<style>`, are kept as-is.
+///
+/// Whitespace between block-level elements, and at the start and end of text next to them, is
+/// removed. Browsers ignore such whitespace in most cases, but CSS can make it significant (e.g.
+/// `white-space: pre`), so prettified output should only be used for debugging.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let s = prettify("<ul><li>one</li><li>two <b>2</b></li></ul><pre>a\n  b</pre>");
+///
+/// assert_eq!(
+///     s,
+///     "<ul>\n  <li>one</li>\n  <li>two <b>2</b></li>\n</ul>\n<pre>a\n  b</pre>"
+/// );
+/// ```
+pub fn prettify(html: &str) -> String {
+    let nodes = parse(html);
+    let mut out = String::with_capacity(html.len() + html.len() / 2);
+
+    print_nodes(html, &nodes, 0, &mut out);
+    out
+}
+
+struct Node {
+    /// Start of the node in the source.
+    start: usize,
+    /// End of the node in the source.
+    end: usize,
+    kind: Kind,
+}
+
+enum Kind {
+    Element {
+        name: String,
+        /// End of the start tag.
+        tag_end: usize,
+        /// Start of the end tag, if any.
+        end_tag: Option<usize>,
+        children: Vec<Node>,
+    },
+    Text,
+    /// Comment, doctype or other declaration.
+    Other,
+}
+
+impl Node {
+    fn is_block(&self, src: &str) -> bool {
+        match self.kind {
+            Kind::Element { ref name, .. } => BLOCK_TAGS.contains(&name.as_str()),
+            Kind::Other => !src[self.start..].starts_with("<!--"),
+            Kind::Text => false,
+        }
+    }
+}
+
+struct Open {
+    name: String,
+    start: usize,
+    tag_end: usize,
+    children: Vec<Node>,
+}
+
+impl Open {
+    fn close(self, end_tag: Option<usize>, end: usize) -> Node {
+        Node {
+            start: self.start,
+            end,
+            kind: Kind::Element {
+                name: self.name,
+                tag_end: self.tag_end,
+                end_tag,
+                children: self.children,
+            },
+        }
+    }
+}
+
+fn parse(src: &str) -> Vec<Node> {
+    let bytes = src.as_bytes();
+    let mut root = vec![];
+    let mut stack: Vec<Open> = vec![];
+    let mut i = 0;
+
+    fn push(root: &mut Vec<Node>, stack: &mut [Open], node: Node) {
+        match stack.last_mut() {
+            Some(open) => open.children.push(node),
+            None => root.push(node),
+        }
+    }
+
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+
+        if rest.starts_with(b"<!--") {
+            let end = find(src, i + 4, "-->").map_or(src.len(), |end| end + 3);
+
+            push(&mut root, &mut stack, other(i, end));
+            i = end;
+        } else if rest.starts_with(b"<!") || rest.starts_with(b"<?") {
+            let end = find(src, i, ">").map_or(src.len(), |end| end + 1);
+
+            push(&mut root, &mut stack, other(i, end));
+            i = end;
+        } else if rest.starts_with(b"</") && rest.get(2).is_some_and(u8::is_ascii_alphabetic) {
+            let end = tag_end(bytes, i);
+            let name = tag_name(&src[i + 2..]);
+
+            match stack.iter().rposition(|open| open.name == name) {
+                Some(index) => {
+                    // elements that are not closed explicitly end where their parent ends
+                    while stack.len() > index + 1 {
+                        let node = stack.pop().unwrap().close(None, i);
+                        push(&mut root, &mut stack, node);
+                    }
+
+                    let node = stack.pop().unwrap().close(Some(i), end);
+                    push(&mut root, &mut stack, node);
+                }
+
+                // stray end tags are kept as text
+                None => push(&mut root, &mut stack, text(i, end)),
+            }
+
+            i = end;
+        } else if rest.starts_with(b"<") && rest.get(1).is_some_and(u8::is_ascii_alphabetic) {
+            let end = tag_end(bytes, i);
+            let name = tag_name(&src[i + 1..]);
+            let open = Open {
+                name,
+                start: i,
+                tag_end: end,
+                children: vec![],
+            };
+
+            if VOID_TAGS.contains(&open.name.as_str()) || src[i..end].ends_with("/>") {
+                let node = open.close(None, end);
+                push(&mut root, &mut stack, node);
+                i = end;
+            } else if RAW_TEXT_TAGS.contains(&open.name.as_str()) {
+                let (end_tag, end) = match find_end_tag(src, end, &open.name) {
+                    Some(end_tag) => (Some(end_tag), tag_end(bytes, end_tag)),
+                    None => (None, src.len()),
+                };
+
+                let node = open.close(end_tag, end);
+                push(&mut root, &mut stack, node);
+                i = end;
+            } else {
+                stack.push(open);
+                i = end;
+            }
+        } else {
+            let end = find(src, i + 1, "<").unwrap_or(src.len());
+
+            push(&mut root, &mut stack, text(i, end));
+            i = end;
+        }
+    }
+
+    while let Some(open) = stack.pop() {
+        let node = open.close(None, src.len());
+        push(&mut root, &mut stack, node);
+    }
+
+    root
+}
+
+fn text(start: usize, end: usize) -> Node {
+    Node {
+        start,
+        end,
+        kind: Kind::Text,
+    }
+}
+
+fn other(start: usize, end: usize) -> Node {
+    Node {
+        start,
+        end,
+        kind: Kind::Other,
+    }
+}
+
+fn find(src: &str, from: usize, pattern: &str) -> Option<usize> {
+    src[from..].find(pattern).map(|index| from + index)
+}
+
+/// Returns the end of the tag starting at `start`, skipping over quoted attribute values.
+fn tag_end(bytes: &[u8], start: usize) -> usize {
+    let mut quote = None;
+
+    for (i, &c) in bytes.iter().enumerate().skip(start) {
+        match (quote, c) {
+            (None, b'"' | b'\'') => quote = Some(c),
+            (None, b'>') => return i + 1,
+            (Some(q), c) if q == c => quote = None,
+            _ => {}
+        }
+    }
+
+    bytes.len()
+}
+
+fn tag_name(src: &str) -> String {
+    let end = src
+        .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+        .unwrap_or(src.len());
+
+    src[..end].to_ascii_lowercase()
+}
+
+/// Returns the start of the end tag of a raw text element, which is matched case-insensitively.
+fn find_end_tag(src: &str, from: usize, name: &str) -> Option<usize> {
+    let bytes = src.as_bytes();
+
+    (from..bytes.len()).find(|&i| {
+        let rest = &bytes[i..];
+
+        rest.starts_with(b"</")
+            && rest.len() >= name.len() + 2
+            && rest[2..name.len() + 2].eq_ignore_ascii_case(name.as_bytes())
+    })
+}
+
+fn print_nodes(src: &str, nodes: &[Node], depth: usize, out: &mut String) {
+    let mut run: Option<(usize, usize)> = None;
+
+    for node in nodes {
+        if node.is_block(src) {
+            if let Some((start, end)) = run.take() {
+                print_run(&src[start..end], depth, out);
+            }
+
+            print_block(src, node, depth, out);
+        } else {
+            run = Some(match run {
+                Some((start, _)) => (start, node.end),
+                None => (node.start, node.end),
+            });
+        }
+    }
+
+    if let Some((start, end)) = run {
+        print_run(&src[start..end], depth, out);
+    }
+}
+
+fn print_run(run: &str, depth: usize, out: &mut String) {
+    let run = run.trim_matches(|c: char| c.is_ascii_whitespace());
+
+    if !run.is_empty() {
+        print_line(run, depth, out);
+    }
+}
+
+fn print_block(src: &str, node: &Node, depth: usize, out: &mut String) {
+    match node.kind {
+        Kind::Element {
+            ref name,
+            tag_end,
+            end_tag,
+            ref children,
+        } if !VERBATIM_TAGS.contains(&name.as_str())
+            && children.iter().any(|child| child.is_block(src)) =>
+        {
+            print_line(&src[node.start..tag_end], depth, out);
+            print_nodes(src, children, depth + 1, out);
+
+            if let Some(end_tag) = end_tag {
+                print_line(&src[end_tag..node.end], depth, out);
+            }
+        }
+
+        _ => print_line(&src[node.start..node.end], depth, out),
+    }
+}
+
+fn print_line(line: &str, depth: usize, out: &mut String) {
+    if !out.is_empty() {
+        out.push('\n');
+    }
+
+    for _ in 0..depth {
+        out.push_str(PRETTY_INDENT);
+    }
+
+    out.push_str(line);
+}
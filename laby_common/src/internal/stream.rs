@@ -0,0 +1,82 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use super::Buffer;
+use crate::Render;
+use core::fmt;
+
+/// Default capacity of the buffer used by [`render_fmt`] and `render_io`, which is also the
+/// maximum size of each chunk written to the output.
+pub const STREAM_CHUNK_SIZE: usize = 16384;
+
+/// Renders the given value into a [`fmt::Write`] in chunks of at most [`STREAM_CHUNK_SIZE`]
+/// bytes.
+///
+/// Unlike rendering into a single [`Buffer`] and writing the result afterwards, the memory used
+/// by this function stays bounded regardless of the size of the rendered output. See
+/// [`Buffer::stream`] for details.
+///
+/// If the writer returns an error, rendering continues but nothing more is written, and the
+/// error is returned after rendering completes.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut s = String::new();
+/// render_fmt("a < b", &mut s).unwrap();
+///
+/// assert_eq!(s, "a &lt; b");
+/// ```
+pub fn render_fmt<W, R>(value: R, writer: &mut W) -> fmt::Result
+where
+    W: fmt::Write + ?Sized,
+    R: Render,
+{
+    let mut result = Ok(());
+    let mut sink = |chunk: &str| {
+        if result.is_ok() {
+            result = writer.write_str(chunk);
+        }
+    };
+
+    Buffer::stream(STREAM_CHUNK_SIZE, &mut sink, |buffer| value.render(buffer));
+    result
+}
+
+/// Renders the given value into an [`io::Write`](std::io::Write) in chunks of at most
+/// [`STREAM_CHUNK_SIZE`] bytes.
+///
+/// This function is only available with the `std` feature. It behaves like [`render_fmt`] but
+/// writes to a byte-oriented writer such as a file or a socket.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut v = Vec::new();
+/// render_io("a < b", &mut v).unwrap();
+///
+/// assert_eq!(v, b"a &lt; b");
+/// ```
+#[cfg(feature = "std")]
+pub fn render_io<W, R>(value: R, writer: &mut W) -> std::io::Result<()>
+where
+    W: std::io::Write + ?Sized,
+    R: Render,
+{
+    let mut result = Ok(());
+    let mut sink = |chunk: &str| {
+        if result.is_ok() {
+            result = writer.write_all(chunk.as_bytes());
+        }
+    };
+
+    Buffer::stream(STREAM_CHUNK_SIZE, &mut sink, |buffer| value.render(buffer));
+    result
+}
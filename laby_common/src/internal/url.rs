@@ -0,0 +1,254 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use super::Buffer;
+
+/// URL that replaces URLs with unsafe schemes.
+///
+/// Navigating to this URL does nothing in all browsers.
+pub const INVALID_URL: &str = "about:invalid";
+
+/// URL schemes that are considered safe by [`is_safe_url`].
+pub const SAFE_URL_SCHEMES: [&str; 5] = ["http", "https", "ftp", "mailto", "tel"];
+
+/// Kind of URL contained in an attribute value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UrlKind {
+    /// Single URL, escaped using [`escape_url`].
+    Url,
+
+    /// List of image candidates, escaped using [`escape_srcset`].
+    Srcset,
+}
+
+/// Returns the kind of URL contained in the value of the given attribute, or [`None`] if the
+/// attribute does not contain a URL.
+///
+/// Attribute names are compared case-insensitively.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// assert_eq!(url_attribute_kind("href"), Some(UrlKind::Url));
+/// assert_eq!(url_attribute_kind("SRCSET"), Some(UrlKind::Srcset));
+/// assert_eq!(url_attribute_kind("title"), None);
+/// ```
+pub fn url_attribute_kind(name: &str) -> Option<UrlKind> {
+    const URL_ATTRS: [&str; 11] = [
+        "action",
+        "background",
+        "cite",
+        "data",
+        "formaction",
+        "href",
+        "longdesc",
+        "manifest",
+        "poster",
+        "src",
+        "xlink:href",
+    ];
+
+    const SRCSET_ATTRS: [&str; 2] = ["imagesrcset", "srcset"];
+
+    if URL_ATTRS.iter().any(|attr| attr.eq_ignore_ascii_case(name)) {
+        Some(UrlKind::Url)
+    } else if SRCSET_ATTRS
+        .iter()
+        .any(|attr| attr.eq_ignore_ascii_case(name))
+    {
+        Some(UrlKind::Srcset)
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if the given URL is relative or has a scheme in [`SAFE_URL_SCHEMES`].
+///
+/// Like browsers, this function ignores leading spaces and control characters and all tabs and
+/// newlines in the URL when parsing its scheme, so that `" java\tscript:"` is detected as the
+/// `javascript` scheme. Schemes are compared case-insensitively.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// assert!(is_safe_url("https://example.com"));
+/// assert!(is_safe_url("/path?query#hash"));
+/// assert!(is_safe_url("mailto:user@example.com"));
+///
+/// assert!(!is_safe_url("javascript:alert(1)"));
+/// assert!(!is_safe_url(" JAVA\nSCRIPT:alert(1)"));
+/// assert!(!is_safe_url("data:text/html,<script>alert(1)</script>"));
+/// ```
+pub fn is_safe_url(url: &str) -> bool {
+    let mut scheme = [0; 6];
+    let mut len = 0;
+
+    for c in url
+        .bytes()
+        .skip_while(|&c| c <= b' ')
+        .filter(|&c| !matches!(c, b'\t' | b'\n' | b'\r'))
+    {
+        match c {
+            b':' => {
+                return len <= scheme.len()
+                    && SAFE_URL_SCHEMES
+                        .iter()
+                        .any(|safe| safe.as_bytes() == &scheme[..len]);
+            }
+
+            // scheme must start with a letter
+            c if len == 0 && !c.is_ascii_alphabetic() => return true,
+
+            c if c.is_ascii_alphanumeric() || matches!(c, b'+' | b'-' | b'.') => {
+                if len < scheme.len() {
+                    scheme[len] = c.to_ascii_lowercase();
+                }
+
+                len += 1;
+            }
+
+            // not a scheme, so the url is relative
+            _ => return true,
+        }
+    }
+
+    true
+}
+
+/// Escapes a URL for inclusion in an HTML attribute value.
+///
+/// If the URL is not [safe](is_safe_url), it is replaced with [`INVALID_URL`]. Otherwise, leading
+/// and trailing spaces and control characters are removed, and spaces, control characters, quotes,
+/// angle brackets, backticks and non-ASCII characters are percent-encoded. Existing
+/// percent-encoded sequences are left as-is, and `&` is escaped as `&amp;`.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut buf = Buffer::new();
+///
+/// escape_url("/search?q=a b&lang=한", &mut buf);
+/// buf.push_str(" ");
+/// escape_url("javascript:alert(1)", &mut buf);
+///
+/// assert_eq!(
+///     buf.into_string(),
+///     "/search?q=a%20b&amp;lang=%ED%95%9C about:invalid"
+/// );
+/// ```
+pub fn escape_url(feed: &str, buf: &mut Buffer) {
+    let url = feed.trim_matches(|c: char| c <= ' ');
+
+    if is_safe_url(url) {
+        encode(url, buf);
+    } else {
+        buf.push_str(INVALID_URL);
+    }
+}
+
+/// Escapes a [`srcset`][1] attribute value for inclusion in an HTML attribute value.
+///
+/// The value is split into image candidates, each consisting of a URL and optional descriptors
+/// (e.g. `2x` or `100w`). Each URL is escaped using [`escape_url`], and the candidates are joined
+/// again using `", "`.
+///
+/// [1]: https://developer.mozilla.org/en-US/docs/Web/HTML/Element/img#attr-srcset
+///
+/// # Example
+///
+/// ```
+/// # use laby_common::internal::*;
+/// let mut buf = Buffer::new();
+/// escape_srcset("a.png, b.png 2x,  javascript:c 3x", &mut buf);
+///
+/// assert_eq!(buf.into_string(), "a.png, b.png 2x, about:invalid 3x");
+/// ```
+pub fn escape_srcset(feed: &str, buf: &mut Buffer) {
+    let mut rest = feed;
+    let mut first = true;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+
+        if rest.is_empty() {
+            break;
+        }
+
+        let (url, descriptors);
+        let end = rest
+            .find(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(rest.len());
+
+        if rest[..end].ends_with(',') {
+            // trailing commas terminate the candidate without descriptors
+            url = rest[..end].trim_end_matches(',');
+            descriptors = "";
+            rest = &rest[end..];
+        } else {
+            let tail = &rest[end..];
+            let next = tail.find(',').unwrap_or(tail.len());
+
+            url = &rest[..end];
+            descriptors = &tail[..next];
+            rest = &tail[next..];
+        }
+
+        if first {
+            first = false;
+        } else {
+            buf.push_str(", ");
+        }
+
+        escape_url(url, buf);
+
+        for descriptor in descriptors.split_ascii_whitespace() {
+            buf.push(' ');
+            encode(descriptor, buf);
+        }
+    }
+}
+
+fn encode(feed: &str, buf: &mut Buffer) {
+    const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+    let bytes = feed.as_bytes();
+    let mut start = 0;
+
+    // every byte of a non-ascii character is encoded, so `start` is only ever in the middle of a
+    // character when there is nothing to copy
+    let copy = |buf: &mut Buffer, start: usize, end: usize| {
+        if start < end {
+            buf.push_str(&feed[start..end]);
+        }
+    };
+
+    for (i, &c) in bytes.iter().enumerate() {
+        match c {
+            b'&' => {
+                copy(buf, start, i);
+                buf.push_str("&amp;");
+            }
+
+            0..=b' ' | 0x7f.. | b'"' | b'\'' | b'<' | b'>' | b'`' => {
+                copy(buf, start, i);
+                buf.push('%');
+                buf.push(DIGITS[(c >> 4) as usize] as char);
+                buf.push(DIGITS[(c & 0xf) as usize] as char);
+            }
+
+            _ => continue,
+        }
+
+        start = i + 1;
+    }
+
+    copy(buf, start, bytes.len());
+}
@@ -17,8 +17,14 @@
 #![no_std]
 #![deny(missing_docs)]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod internal;
 mod render;
+mod render_async;
+mod render_try;
 
 pub use render::*;
+pub use render_async::*;
+pub use render_try::*;
@@ -6,10 +6,12 @@
 //
 //   https://opensource.org/licenses/MIT
 //
-use crate::internal::{escape, Buffer};
+use crate::internal::{escape, Buffer, EscapeContext};
 use alloc::{
     borrow::{Cow, ToOwned},
+    boxed::Box,
     string::String,
+    vec::Vec,
 };
 use core::{
     fmt::{Arguments, Write},
@@ -56,6 +58,195 @@ use core::{
 pub trait Render {
     /// Formats this value into the given output buffer, consuming itself.
     fn render(self, buffer: &mut Buffer);
+
+    /// Returns an estimate of the number of bytes this value renders.
+    ///
+    /// This is used to preallocate output buffers, for example by the `render!` macro of laby.
+    /// The estimate does not have to be exact, and should be cheap to compute; strings are
+    /// estimated by their unescaped length, for example. The default implementation returns `0`.
+    ///
+    /// Nodes created by element macros return the length of their static markup plus the
+    /// estimates of their children and attribute values.
+    #[inline]
+    fn size_hint(&self) -> usize {
+        0
+    }
+}
+
+/// Formats a value into an HTML representation by reference, without consuming it.
+///
+/// This trait is implemented automatically for every type whose references implement [`Render`].
+/// Most types provided by laby implement [`Render`] for their references, including strings,
+/// numbers, collections and nodes generated by element macros whose captured values can be
+/// rendered by reference. This allows a value to be rendered many times, such as a layout that
+/// is built once and rendered for every response.
+///
+/// Use this trait as a bound when a value is only borrowed. To implement rendering by reference
+/// for a custom type, implement [`Render`] for a reference to it instead.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common as laby;
+/// use laby::{Render, RenderRef};
+/// use laby::internal::Buffer;
+///
+/// fn render_twice<R: RenderRef + ?Sized>(value: &R) -> String {
+///     let mut buffer = Buffer::new();
+///     value.render_ref(&mut buffer);
+///     value.render_ref(&mut buffer);
+///     buffer.into_string()
+/// }
+///
+/// assert_eq!(render_twice("a < b"), "a &lt; ba &lt; b");
+/// assert_eq!(render_twice(&vec![1, 2]), "1212");
+/// ```
+pub trait RenderRef {
+    /// Formats this value into the given output buffer, without consuming itself.
+    fn render_ref(&self, buffer: &mut Buffer);
+
+    /// Returns an estimate of the number of bytes this value renders.
+    ///
+    /// See [`Render::size_hint`].
+    fn size_hint_ref(&self) -> usize;
+}
+
+impl<T> RenderRef for T
+where
+    T: ?Sized,
+    for<'a> &'a T: Render,
+{
+    #[inline]
+    fn render_ref(&self, buffer: &mut Buffer) {
+        self.render(buffer);
+    }
+
+    #[inline]
+    fn size_hint_ref(&self) -> usize {
+        Render::size_hint(&self)
+    }
+}
+
+/// Formats a boxed value into an HTML representation, consuming itself.
+///
+/// [`Render`] consumes values by value, so it cannot be used as a trait object. This trait is
+/// implemented automatically for every type that implements [`Render`], and can be used as a
+/// trait object instead. `Box<dyn BoxRender>`, as well as its [`Send`] and [`Sync`] variants,
+/// implements [`Render`], which makes it possible to store values of different types together,
+/// such as in a [`Vec`] or a lookup table of components, or to return them from different
+/// branches of a `match` expression.
+///
+/// Values that should be rendered many times can be stored as `Box<dyn RenderRef>` instead.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common as laby;
+/// use laby::{BoxRender, Render};
+/// use laby::internal::Buffer;
+///
+/// let values: Vec<Box<dyn BoxRender>> = vec![
+///     "a < b".into_boxed(),
+///     Box::new(1),
+///     Some('c').into_boxed(),
+/// ];
+///
+/// let mut buffer = Buffer::new();
+/// values.render(&mut buffer);
+/// assert_eq!(buffer.into_string(), "a &lt; b1c");
+/// ```
+pub trait BoxRender {
+    /// Formats this boxed value into the given output buffer, consuming itself.
+    fn render_box(self: Box<Self>, buffer: &mut Buffer);
+
+    /// Returns an estimate of the number of bytes this boxed value renders.
+    ///
+    /// See [`Render::size_hint`].
+    fn size_hint_box(&self) -> usize;
+
+    /// Moves this value into a box, erasing its type.
+    #[inline]
+    fn into_boxed<'a>(self) -> Box<dyn BoxRender + 'a>
+    where
+        Self: Sized + 'a,
+    {
+        Box::new(self)
+    }
+}
+
+impl<R> BoxRender for R
+where
+    R: Render,
+{
+    #[inline]
+    fn render_box(self: Box<Self>, buffer: &mut Buffer) {
+        (*self).render(buffer);
+    }
+
+    #[inline]
+    fn size_hint_box(&self) -> usize {
+        self.size_hint()
+    }
+}
+
+macro_rules! impl_box_dyn {
+    ($($bounds:tt)*) => {
+        impl Render for Box<dyn BoxRender $($bounds)* + '_> {
+            #[inline]
+            fn render(self, buffer: &mut Buffer) {
+                self.render_box(buffer);
+            }
+
+            #[inline]
+            fn size_hint(&self) -> usize {
+                (**self).size_hint_box()
+            }
+        }
+
+        impl Render for Box<dyn RenderRef $($bounds)* + '_> {
+            #[inline]
+            fn render(self, buffer: &mut Buffer) {
+                (*self).render_ref(buffer);
+            }
+
+            #[inline]
+            fn size_hint(&self) -> usize {
+                (**self).size_hint_ref()
+            }
+        }
+
+        impl Render for &Box<dyn RenderRef $($bounds)* + '_> {
+            #[inline]
+            fn render(self, buffer: &mut Buffer) {
+                (**self).render_ref(buffer);
+            }
+
+            #[inline]
+            fn size_hint(&self) -> usize {
+                (***self).size_hint_ref()
+            }
+        }
+    };
+}
+
+impl_box_dyn!();
+impl_box_dyn!(+ Send);
+impl_box_dyn!(+ Send + Sync);
+
+impl<'a, T> Render for &&'a T
+where
+    T: ?Sized,
+    &'a T: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        (*self).render(buffer);
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        (**self).size_hint()
+    }
 }
 
 impl Render for () {
@@ -66,6 +257,11 @@ impl Render for () {
 impl Render for char {
     #[inline]
     fn render(self, buffer: &mut Buffer) {
+        // xhtml replaces characters that are not allowed in xml
+        if buffer.context() != EscapeContext::Html || buffer.xhtml() {
+            return escape(self.encode_utf8(&mut [0; 4]), buffer);
+        }
+
         match self {
             '"' => buffer.push_str("&quot;"),
             '&' => buffer.push_str("&amp;"),
@@ -75,6 +271,11 @@ impl Render for char {
             v => buffer.push(v),
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.len_utf8()
+    }
 }
 
 impl Render for bool {
@@ -91,12 +292,18 @@ macro_rules! impl_str {
             fn render(self, buffer: &mut Buffer) {
                 escape(self.as_ref(), buffer);
             }
+
+            #[inline]
+            fn size_hint(&self) -> usize {
+                AsRef::<str>::as_ref(self).len()
+            }
         }
     };
 }
 
 impl_str!(&str);
 impl_str!(String);
+impl_str!(&String);
 
 macro_rules! impl_int {
     ($type:ty) => {
@@ -110,7 +317,14 @@ macro_rules! impl_int {
                 // SAFETY: `MAX_LEN < 40` and then does not overflows `isize::MAX`.
                 // Also `b.len()` should be always less than or equal to `isize::MAX`.
                 unsafe {
-                    buffer.reserve_small(Self::MAX_LEN);
+                    // near the maximum length, the number is formatted on the stack first so that
+                    // the buffer only fails if it does not actually fit
+                    if buffer.try_reserve(Self::MAX_LEN).is_err() {
+                        let mut bytes = [0; 40];
+                        let l = itoap::write_to_ptr(bytes.as_mut_ptr(), self);
+                        return buffer.push_str(core::str::from_utf8_unchecked(&bytes[..l]));
+                    }
+
                     let ptr = buffer.as_mut_ptr().add(buffer.len());
 
                     // SAFETY: `MAX_LEN` is always greater than zero, so
@@ -173,7 +387,12 @@ macro_rules! impl_float {
 
                 if self.is_finite() {
                     unsafe {
-                        buffer.reserve_small($min);
+                        if buffer.try_reserve($min).is_err() {
+                            let mut bytes = [0; $min];
+                            let l = $fn(self, bytes.as_mut_ptr());
+                            return buffer.push_str(core::str::from_utf8_unchecked(&bytes[..l]));
+                        }
+
                         let ptr = buffer.as_mut_ptr().add(buffer.len());
                         let l = $fn(self, ptr);
                         buffer.advance(l);
@@ -193,6 +412,45 @@ macro_rules! impl_float {
 impl_float!(f32, 16, format32);
 impl_float!(f64, 24, format64);
 
+macro_rules! impl_copy_ref {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl Render for &$type {
+                #[inline]
+                fn render(self, buffer: &mut Buffer) {
+                    (*self).render(buffer);
+                }
+
+                #[inline]
+                fn size_hint(&self) -> usize {
+                    (**self).size_hint()
+                }
+            }
+        )*
+    };
+}
+
+impl_copy_ref!((), char, bool, f32, f64);
+impl_copy_ref!(u8, u16, u32, u64, u128, usize);
+impl_copy_ref!(i8, i16, i32, i64, i128, isize);
+impl_copy_ref!(
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize
+);
+impl_copy_ref!(
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize
+);
+impl_copy_ref!(Arguments<'_>);
+
 impl<R> Render for Option<R>
 where
     R: Render,
@@ -203,11 +461,243 @@ where
             value.render(buffer);
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.as_ref().map_or(0, R::size_hint)
+    }
+}
+
+impl<'a, R> Render for &'a Option<R>
+where
+    &'a R: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        if let Some(value) = self {
+            value.render(buffer);
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.as_ref().map_or(0, |value| value.size_hint())
+    }
+}
+
+impl<T, E> Render for Result<T, E>
+where
+    T: Render,
+    E: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        match self {
+            Ok(value) => value.render(buffer),
+            Err(value) => value.render(buffer),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        match self {
+            Ok(value) => value.size_hint(),
+            Err(value) => value.size_hint(),
+        }
+    }
+}
+
+impl<'a, T, E> Render for &'a Result<T, E>
+where
+    &'a T: Render,
+    &'a E: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        match self {
+            Ok(value) => value.render(buffer),
+            Err(value) => value.render(buffer),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        match self {
+            Ok(value) => value.size_hint(),
+            Err(value) => value.size_hint(),
+        }
+    }
+}
+
+impl<R> Render for Vec<R>
+where
+    R: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        for item in self {
+            item.render(buffer);
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.iter().map(R::size_hint).sum()
+    }
+}
+
+impl<'a, R> Render for &'a Vec<R>
+where
+    &'a R: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        self.as_slice().render(buffer);
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.as_slice().size_hint()
+    }
+}
+
+impl<R> Render for Box<[R]>
+where
+    R: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        Vec::from(self).render(buffer);
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.iter().map(R::size_hint).sum()
+    }
+}
+
+impl<'a, R> Render for &'a Box<[R]>
+where
+    &'a R: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        (**self).render(buffer);
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        (&***self).size_hint()
+    }
+}
+
+impl<R, const N: usize> Render for [R; N]
+where
+    R: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        for item in self {
+            item.render(buffer);
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.iter().map(R::size_hint).sum()
+    }
+}
+
+impl<'a, R, const N: usize> Render for &'a [R; N]
+where
+    &'a R: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        self.as_slice().render(buffer);
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.as_slice().size_hint()
+    }
+}
+
+impl<'a, R> Render for &'a [R]
+where
+    &'a R: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        for item in self {
+            item.render(buffer);
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.iter().map(|item| item.size_hint()).sum()
+    }
 }
 
+macro_rules! impl_tuple {
+    ($($name:ident)+) => {
+        impl<$($name),+> Render for ($($name,)+)
+        where
+            $($name: Render),+
+        {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn render(self, buffer: &mut Buffer) {
+                let ($($name,)+) = self;
+                $($name.render(buffer);)+
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn size_hint(&self) -> usize {
+                let ($($name,)+) = self;
+                0 $(+ $name.size_hint())+
+            }
+        }
+
+        impl<'a, $($name),+> Render for &'a ($($name,)+)
+        where
+            $(&'a $name: Render),+
+        {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn render(self, buffer: &mut Buffer) {
+                let ($($name,)+) = self;
+                $($name.render(buffer);)+
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn size_hint(&self) -> usize {
+                let ($($name,)+) = *self;
+                0 $(+ $name.size_hint())+
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A B);
+impl_tuple!(A B C);
+impl_tuple!(A B C D);
+impl_tuple!(A B C D E);
+impl_tuple!(A B C D E F);
+impl_tuple!(A B C D E F G);
+impl_tuple!(A B C D E F G H);
+impl_tuple!(A B C D E F G H I);
+impl_tuple!(A B C D E F G H I J);
+impl_tuple!(A B C D E F G H I J K);
+impl_tuple!(A B C D E F G H I J K L);
+
 impl<'a, R> Render for Cow<'a, R>
 where
-    R: ToOwned,
+    R: ToOwned + ?Sized,
     &'a R: Render,
     R::Owned: Render,
 {
@@ -218,13 +708,37 @@ where
             Cow::Borrowed(value) => value.render(buffer),
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        match self {
+            Cow::Owned(value) => value.size_hint(),
+            Cow::Borrowed(value) => value.size_hint(),
+        }
+    }
+}
+
+impl<'a, 'b, R> Render for &'b Cow<'a, R>
+where
+    R: ToOwned + ?Sized,
+    &'b R: Render,
+{
+    #[inline]
+    fn render(self, buffer: &mut Buffer) {
+        (**self).render(buffer);
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        (&***self).size_hint()
+    }
 }
 
 impl<'a> Render for Arguments<'a> {
     fn render(self, buffer: &mut Buffer) {
-        struct EscapingBufferWriter<'a>(&'a mut Buffer);
+        struct EscapingBufferWriter<'a, 's>(&'a mut Buffer<'s>);
 
-        impl<'a> Write for EscapingBufferWriter<'a> {
+        impl Write for EscapingBufferWriter<'_, '_> {
             fn write_str(&mut self, s: &str) -> core::fmt::Result {
                 Ok(s.render(self.0))
             }
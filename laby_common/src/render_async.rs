@@ -0,0 +1,395 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use crate::{internal::Buffer, Render};
+use alloc::{boxed::Box, string::String, sync::Arc};
+use core::{
+    cell::UnsafeCell,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Context, Poll},
+};
+
+/// Formats a value into an HTML representation asynchronously.
+///
+/// This trait is the asynchronous counterpart of [`Render`]. It allows a value to wait for data,
+/// such as the result of a database query, in the middle of rendering. When rendered using
+/// [`render_stream`], everything that was rendered before the value starts waiting is emitted as a
+/// chunk, so that the beginning of a document (e.g. its `<head>`) can be sent to the client before
+/// the rest of it is ready.
+///
+/// All types implementing [`Render`] also implement this trait, so existing markup can be rendered
+/// asynchronously without changes. Synchronous values can be rendered from within an asynchronous
+/// value using [`AsyncBuffer::render`], and other asynchronous values can be awaited by calling
+/// [`render_async`](Self::render_async) on them with a clone of the buffer. Asynchronous values
+/// can also be embedded in markup by marking them with the `awaited!` macro of laby.
+///
+/// The returned future is [`Send`] if the value and everything it holds across an await point
+/// are, but this cannot be relied upon when the type of the value is generic. Implement
+/// [`SendAsyncRender`] as well in that case.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common as laby;
+/// use laby::{AsyncBuffer, AsyncRender};
+/// use laby::internal::escape;
+///
+/// struct Greeting;
+///
+/// impl AsyncRender for Greeting {
+///     async fn render_async(self, buffer: AsyncBuffer) {
+///         let name = async { "laby" }.await; // e.g. a database query
+///
+///         buffer.with(|buffer| {
+///             buffer.push_str("hello, ");
+///             escape(name, buffer);
+///         });
+///     }
+/// }
+/// ```
+pub trait AsyncRender {
+    /// Formats this value into the given output buffer asynchronously, consuming itself.
+    fn render_async(self, buffer: AsyncBuffer) -> impl Future<Output = ()>;
+}
+
+impl<R> AsyncRender for R
+where
+    R: Render,
+{
+    #[inline]
+    fn render_async(self, buffer: AsyncBuffer) -> impl Future<Output = ()> {
+        buffer.render(self);
+        core::future::ready(())
+    }
+}
+
+/// [`AsyncRender`] whose future can be sent to another thread.
+///
+/// Values that are generic over [`AsyncRender`] cannot be rendered on a multithreaded executor,
+/// because the future returned by [`AsyncRender::render_async`] is not known to be [`Send`]. This
+/// trait provides that guarantee, and such values can be rendered using [`render_stream_send`].
+///
+/// All types implementing [`Render`] and [`Send`] also implement this trait. Other types usually
+/// implement it by forwarding to [`AsyncRender::render_async`], which only compiles if the
+/// returned future is actually [`Send`]. Nodes created by element macros implement this trait if
+/// all their children do.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common as laby;
+/// use laby::{AsyncBuffer, AsyncRender, SendAsyncRender};
+/// use core::future::Future;
+///
+/// struct Greeting;
+///
+/// impl AsyncRender for Greeting {
+///     async fn render_async(self, buffer: AsyncBuffer) {
+///         let name = async { "laby" }.await;
+///         buffer.render(name);
+///     }
+/// }
+///
+/// impl SendAsyncRender for Greeting {
+///     fn render_async_send(self, buffer: AsyncBuffer) -> impl Future<Output = ()> + Send {
+///         self.render_async(buffer)
+///     }
+/// }
+/// ```
+pub trait SendAsyncRender: AsyncRender + Send {
+    /// Formats this value into the given output buffer asynchronously, consuming itself.
+    fn render_async_send(self, buffer: AsyncBuffer) -> impl Future<Output = ()> + Send;
+}
+
+impl<R> SendAsyncRender for R
+where
+    R: Render + Send,
+{
+    #[inline]
+    fn render_async_send(self, buffer: AsyncBuffer) -> impl Future<Output = ()> + Send {
+        buffer.render(self);
+        core::future::ready(())
+    }
+}
+
+/// Handle to the output buffer of an asynchronous rendering operation.
+///
+/// This handle is cheap to clone, and all clones refer to the same underlying [`Buffer`]. The
+/// buffer can only be accessed synchronously through [`with`](Self::with) or
+/// [`render`](Self::render), so that it can be drained by [`RenderStream`] whenever rendering is
+/// suspended.
+#[derive(Clone, Default)]
+pub struct AsyncBuffer {
+    shared: Arc<SharedBuffer>,
+}
+
+#[derive(Default)]
+struct SharedBuffer {
+    locked: AtomicBool,
+    buffer: UnsafeCell<Buffer<'static>>,
+    /// Contents written while the buffer was being accessed, to be appended to it.
+    deferred_locked: AtomicBool,
+    deferred: UnsafeCell<Buffer<'static>>,
+    /// Whether the buffer is in xhtml mode and in foreign content, for buffers created by nested
+    /// accesses.
+    xhtml: AtomicBool,
+    foreign: AtomicBool,
+}
+
+// SAFETY: `Buffer` is `Send`, and all access to it is synchronized by the locks.
+unsafe impl Send for SharedBuffer {}
+unsafe impl Sync for SharedBuffer {}
+
+struct Unlock<'a>(&'a AtomicBool);
+
+impl Drop for Unlock<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl SharedBuffer {
+    /// Calls `f` with exclusive access to the deferred contents.
+    fn with_deferred<T>(&self, f: impl FnOnce(&mut Buffer<'static>) -> T) -> T {
+        // the lock is never held while calling user code, so this does not spin for long
+        while self
+            .deferred_locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+
+        let _unlock = Unlock(&self.deferred_locked);
+
+        // SAFETY: the lock guarantees exclusive access to the deferred contents.
+        f(unsafe { &mut *self.deferred.get() })
+    }
+
+    /// Appends the deferred contents to the given buffer.
+    fn append_deferred(&self, buffer: &mut Buffer) {
+        self.with_deferred(|deferred| {
+            if !deferred.is_empty() {
+                buffer.push_str(deferred.as_str());
+                deferred.clear();
+            }
+        })
+    }
+}
+
+impl AsyncBuffer {
+    /// Creates a handle to a new empty buffer.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `f` with exclusive access to the underlying buffer.
+    ///
+    /// If the buffer is already being accessed, i.e. if this function is called from within `f`
+    /// itself or concurrently from another thread, `f` is instead called with a new buffer whose
+    /// contents are appended to the underlying buffer after the current access ends. Contents
+    /// written by such nested calls therefore follow the contents written by the outer call, and
+    /// are escaped in the default [`EscapeContext`](crate::internal::EscapeContext), in
+    /// [XHTML](Buffer::set_xhtml) mode and in [foreign content](Buffer::set_foreign) if the
+    /// underlying buffer is.
+    pub fn with<T>(&self, f: impl FnOnce(&mut Buffer<'static>) -> T) -> T {
+        let shared = &*self.shared;
+
+        if shared
+            .locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            let mut buffer = Buffer::new();
+            buffer.set_xhtml(shared.xhtml.load(Ordering::Relaxed));
+            buffer.set_foreign(shared.foreign.load(Ordering::Relaxed));
+
+            let value = f(&mut buffer);
+
+            shared.with_deferred(|deferred| deferred.push_str(buffer.as_str()));
+            return value;
+        }
+
+        let _unlock = Unlock(&shared.locked);
+
+        // SAFETY: the lock guarantees exclusive access to the buffer.
+        let buffer = unsafe { &mut *shared.buffer.get() };
+
+        // contents deferred by a concurrent call after the previous access released the lock
+        shared.append_deferred(buffer);
+        shared.xhtml.store(buffer.xhtml(), Ordering::Relaxed);
+        shared.foreign.store(buffer.foreign(), Ordering::Relaxed);
+
+        let value = f(buffer);
+        shared.append_deferred(buffer);
+        shared.xhtml.store(buffer.xhtml(), Ordering::Relaxed);
+        shared.foreign.store(buffer.foreign(), Ordering::Relaxed);
+        value
+    }
+
+    /// Renders the given synchronous value into the underlying buffer.
+    #[inline]
+    pub fn render(&self, value: impl Render) {
+        self.with(|buffer| value.render(buffer))
+    }
+
+    /// Takes the contents of the underlying buffer, leaving it empty but otherwise unchanged.
+    #[inline]
+    fn take(&self) -> Buffer<'static> {
+        self.with(|buffer| buffer.take_flushed())
+    }
+}
+
+/// Stream of chunks rendered by an [`AsyncRender`] value.
+///
+/// A chunk is emitted whenever rendering is suspended while waiting for data, containing
+/// everything that was rendered since the previous chunk. The last chunk is emitted when rendering
+/// completes. Empty chunks are never emitted.
+///
+/// Chunks can be retrieved using [`next_chunk`](Self::next_chunk) or
+/// [`poll_chunk`](Self::poll_chunk). If the `futures-core` feature is enabled, this type also
+/// implements `Stream<Item = String>`, which can be used as the body of a streaming HTTP response.
+///
+/// Consider using the [`render_stream`] function instead of constructing this type manually.
+pub struct RenderStream<F>
+where
+    F: Future<Output = ()>,
+{
+    buffer: AsyncBuffer,
+    future: Option<Pin<Box<F>>>,
+}
+
+impl<F> RenderStream<F>
+where
+    F: Future<Output = ()>,
+{
+    /// Creates a stream from a future that renders into the given buffer.
+    #[inline]
+    pub fn new(buffer: AsyncBuffer, future: F) -> Self {
+        Self {
+            buffer,
+            future: Some(Box::pin(future)),
+        }
+    }
+
+    /// Attempts to pull out the next chunk, returning [`None`] when rendering has completed and
+    /// all chunks have been emitted.
+    pub fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<String>> {
+        if let Some(ref mut future) = self.future {
+            match future.as_mut().poll(cx) {
+                Poll::Ready(()) => self.future = None,
+
+                Poll::Pending => {
+                    let chunk = self.buffer.take();
+
+                    return if chunk.is_empty() {
+                        Poll::Pending
+                    } else {
+                        Poll::Ready(Some(chunk.into_string()))
+                    };
+                }
+            }
+        }
+
+        let chunk = self.buffer.take();
+
+        Poll::Ready(if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk.into_string())
+        })
+    }
+
+    /// Returns the next chunk, or [`None`] when rendering has completed and all chunks have been
+    /// emitted.
+    #[inline]
+    pub async fn next_chunk(&mut self) -> Option<String> {
+        poll_fn(|cx| self.poll_chunk(cx)).await
+    }
+}
+
+#[cfg(feature = "futures-core")]
+impl<F> futures_core::Stream for RenderStream<F>
+where
+    F: Future<Output = ()>,
+{
+    type Item = String;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<String>> {
+        self.get_mut().poll_chunk(cx)
+    }
+}
+
+/// Renders the given value asynchronously as a [`RenderStream`] of chunks.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common as laby;
+/// use laby::{render_stream, AsyncBuffer, AsyncRender};
+/// # async fn test() {
+///
+/// struct Page;
+///
+/// impl AsyncRender for Page {
+///     async fn render_async(self, buffer: AsyncBuffer) {
+///         buffer.render("head");
+///         // rendering is suspended here until the data is available
+///         let data = async { "body" }.await;
+///         buffer.render(data);
+///     }
+/// }
+///
+/// let mut stream = render_stream(Page);
+///
+/// while let Some(chunk) = stream.next_chunk().await {
+///     // send the chunk to the client
+/// #   let _ = chunk;
+/// }
+/// # }
+/// ```
+#[inline]
+pub fn render_stream<R>(value: R) -> RenderStream<impl Future<Output = ()>>
+where
+    R: AsyncRender,
+{
+    let buffer = AsyncBuffer::new();
+    RenderStream::new(buffer.clone(), value.render_async(buffer))
+}
+
+/// Renders the given value asynchronously as a [`RenderStream`] of chunks that can be sent to
+/// another thread.
+///
+/// This is the same as [`render_stream`], except that the returned stream is always [`Send`], even
+/// when the type of the value is generic.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common as laby;
+/// use laby::{render_stream_send, SendAsyncRender};
+///
+/// fn spawn<R: SendAsyncRender + 'static>(value: R) {
+///     let stream = render_stream_send(value);
+///     std::thread::spawn(move || drop(stream));
+/// }
+/// # spawn("laby");
+/// ```
+#[inline]
+pub fn render_stream_send<R>(value: R) -> RenderStream<impl Future<Output = ()> + Send>
+where
+    R: SendAsyncRender,
+{
+    let buffer = AsyncBuffer::new();
+    RenderStream::new(buffer.clone(), value.render_async_send(buffer))
+}
@@ -0,0 +1,88 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use crate::{internal::Buffer, Render};
+
+/// Formats a value into an HTML representation, or fails with an error.
+///
+/// This trait is the fallible counterpart of [`Render`]. It allows a value to abort rendering
+/// when something goes wrong, such as a data lookup that fails in the middle of a component,
+/// instead of panicking or rendering an error message into the document.
+///
+/// [`Result`] implements this trait if its [`Ok`] value implements [`Render`], failing with its
+/// [`Err`] value. Nodes created by element macros implement this trait instead of [`Render`] if
+/// any of their children are marked with the `fallible!` macro of laby, in which case the first
+/// error returned by a child aborts rendering and is returned from the node. The `try_render!`
+/// macro of laby renders such values and returns the error, if any.
+///
+/// When rendering fails, the output buffer may contain partially rendered markup, which should be
+/// discarded.
+///
+/// # Example
+///
+/// ```
+/// # use laby_common as laby;
+/// use laby::TryRender;
+/// use laby::internal::{Buffer, escape};
+///
+/// struct User<'a> {
+///     name: Option<&'a str>,
+/// }
+///
+/// impl TryRender for User<'_> {
+///     type Error = &'static str;
+///
+///     fn try_render(self, buffer: &mut Buffer) -> Result<(), Self::Error> {
+///         let name = self.name.ok_or("user not found")?;
+///
+///         buffer.push_str("<b>");
+///         escape(name, buffer);
+///         buffer.push_str("</b>");
+///         Ok(())
+///     }
+/// }
+///
+/// let mut buffer = Buffer::new();
+/// assert_eq!(User { name: Some("laby") }.try_render(&mut buffer), Ok(()));
+/// assert_eq!(buffer.into_string(), "<b>laby</b>");
+///
+/// let mut buffer = Buffer::new();
+/// assert_eq!(User { name: None }.try_render(&mut buffer), Err("user not found"));
+/// ```
+pub trait TryRender {
+    /// Type of the error returned when rendering fails.
+    type Error;
+
+    /// Formats this value into the given output buffer, consuming itself.
+    fn try_render(self, buffer: &mut Buffer) -> Result<(), Self::Error>;
+
+    /// Returns an estimate of the number of bytes this value renders if it succeeds.
+    ///
+    /// See [`Render::size_hint`]. The default implementation returns `0`.
+    #[inline]
+    fn size_hint(&self) -> usize {
+        0
+    }
+}
+
+impl<T, E> TryRender for Result<T, E>
+where
+    T: Render,
+{
+    type Error = E;
+
+    #[inline]
+    fn try_render(self, buffer: &mut Buffer) -> Result<(), Self::Error> {
+        self.map(|value| value.render(buffer))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.as_ref().map_or(0, |value| value.size_hint())
+    }
+}
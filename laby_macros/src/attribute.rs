@@ -0,0 +1,545 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use crate::node::{Element, Node};
+use proc_macro2::Span;
+
+/// Attributes that are allowed on all html elements.
+const GLOBAL_ATTRS: [&str; 31] = [
+    "accesskey",
+    "autocapitalize",
+    "autofocus",
+    "class",
+    "contenteditable",
+    "dir",
+    "draggable",
+    "enterkeyhint",
+    "exportparts",
+    "hidden",
+    "id",
+    "inert",
+    "inputmode",
+    "is",
+    "itemid",
+    "itemprop",
+    "itemref",
+    "itemscope",
+    "itemtype",
+    "lang",
+    "nonce",
+    "part",
+    "popover",
+    "role",
+    "slot",
+    "spellcheck",
+    "style",
+    "tabindex",
+    "title",
+    "translate",
+    "writingsuggestions",
+];
+
+/// Returns the attributes that are allowed on the given element in addition to global attributes.
+fn element_attrs(tag: &str) -> &'static [&'static str] {
+    match tag {
+        "a" => &[
+            "download",
+            "href",
+            "hreflang",
+            "ping",
+            "referrerpolicy",
+            "rel",
+            "target",
+            "type",
+        ],
+        "area" => &[
+            "alt",
+            "coords",
+            "download",
+            "href",
+            "ping",
+            "referrerpolicy",
+            "rel",
+            "shape",
+            "target",
+        ],
+        "audio" => &[
+            "autoplay",
+            "controls",
+            "crossorigin",
+            "loop",
+            "muted",
+            "preload",
+            "src",
+        ],
+        "base" => &["href", "target"],
+        "blockquote" | "q" => &["cite"],
+        "button" => &[
+            "command",
+            "commandfor",
+            "disabled",
+            "form",
+            "formaction",
+            "formenctype",
+            "formmethod",
+            "formnovalidate",
+            "formtarget",
+            "name",
+            "popovertarget",
+            "popovertargetaction",
+            "type",
+            "value",
+        ],
+        "canvas" => &["height", "width"],
+        "col" | "colgroup" => &["span"],
+        "data" => &["value"],
+        "del" | "ins" => &["cite", "datetime"],
+        "details" => &["name", "open"],
+        "dialog" => &["open"],
+        "embed" => &["height", "src", "type", "width"],
+        "fieldset" => &["disabled", "form", "name"],
+        "form" => &[
+            "accept-charset",
+            "action",
+            "autocomplete",
+            "enctype",
+            "method",
+            "name",
+            "novalidate",
+            "rel",
+            "target",
+        ],
+        "html" => &["manifest", "version", "xmlns"],
+        "iframe" => &[
+            "allow",
+            "allowfullscreen",
+            "height",
+            "loading",
+            "name",
+            "referrerpolicy",
+            "sandbox",
+            "src",
+            "srcdoc",
+            "width",
+        ],
+        "img" => &[
+            "alt",
+            "crossorigin",
+            "decoding",
+            "fetchpriority",
+            "height",
+            "ismap",
+            "loading",
+            "referrerpolicy",
+            "sizes",
+            "src",
+            "srcset",
+            "usemap",
+            "width",
+        ],
+        "input" => &[
+            "accept",
+            "alt",
+            "autocomplete",
+            "capture",
+            "checked",
+            "dirname",
+            "disabled",
+            "form",
+            "formaction",
+            "formenctype",
+            "formmethod",
+            "formnovalidate",
+            "formtarget",
+            "height",
+            "list",
+            "max",
+            "maxlength",
+            "min",
+            "minlength",
+            "multiple",
+            "name",
+            "pattern",
+            "placeholder",
+            "popovertarget",
+            "popovertargetaction",
+            "readonly",
+            "required",
+            "size",
+            "src",
+            "step",
+            "type",
+            "value",
+            "width",
+        ],
+        "label" | "output" => &["for", "form", "name"],
+        "li" => &["value"],
+        "link" => &[
+            "as",
+            "blocking",
+            "crossorigin",
+            "disabled",
+            "fetchpriority",
+            "href",
+            "hreflang",
+            "imagesizes",
+            "imagesrcset",
+            "integrity",
+            "media",
+            "referrerpolicy",
+            "rel",
+            "sizes",
+            "type",
+        ],
+        "map" | "slot" => &["name"],
+        "meta" => &["charset", "content", "http-equiv", "media", "name"],
+        "meter" => &["form", "high", "low", "max", "min", "optimum", "value"],
+        "object" => &["data", "form", "height", "name", "type", "usemap", "width"],
+        "ol" => &["reversed", "start", "type"],
+        "optgroup" => &["disabled", "label"],
+        "option" => &["disabled", "label", "selected", "value"],
+        "param" => &["name", "value"],
+        "progress" => &["max", "value"],
+        "script" => &[
+            "async",
+            "blocking",
+            "crossorigin",
+            "defer",
+            "fetchpriority",
+            "integrity",
+            "nomodule",
+            "referrerpolicy",
+            "src",
+            "type",
+        ],
+        "select" => &[
+            "autocomplete",
+            "disabled",
+            "form",
+            "multiple",
+            "name",
+            "required",
+            "size",
+        ],
+        "source" => &["height", "media", "sizes", "src", "srcset", "type", "width"],
+        "style" => &["blocking", "media"],
+        "td" => &["colspan", "headers", "rowspan"],
+        "template" => &[
+            "shadowrootclonable",
+            "shadowrootdelegatesfocus",
+            "shadowrootmode",
+            "shadowrootserializable",
+        ],
+        "textarea" => &[
+            "autocomplete",
+            "cols",
+            "dirname",
+            "disabled",
+            "form",
+            "maxlength",
+            "minlength",
+            "name",
+            "placeholder",
+            "readonly",
+            "required",
+            "rows",
+            "wrap",
+        ],
+        "th" => &["abbr", "colspan", "headers", "rowspan", "scope"],
+        "time" => &["datetime"],
+        "track" => &["default", "kind", "label", "src", "srclang"],
+        "video" => &[
+            "autoplay",
+            "controls",
+            "crossorigin",
+            "height",
+            "loop",
+            "muted",
+            "playsinline",
+            "poster",
+            "preload",
+            "src",
+            "width",
+        ],
+        _ => &[],
+    }
+}
+
+const REFERRER_POLICIES: [&str; 9] = [
+    "",
+    "no-referrer",
+    "no-referrer-when-downgrade",
+    "origin",
+    "origin-when-cross-origin",
+    "same-origin",
+    "strict-origin",
+    "strict-origin-when-cross-origin",
+    "unsafe-url",
+];
+
+const FORM_METHODS: [&str; 3] = ["get", "post", "dialog"];
+
+const FORM_ENCTYPES: [&str; 3] = [
+    "application/x-www-form-urlencoded",
+    "multipart/form-data",
+    "text/plain",
+];
+
+const LINK_TYPES: [&str; 37] = [
+    "alternate",
+    "apple-touch-icon",
+    "apple-touch-icon-precomposed",
+    "author",
+    "bookmark",
+    "canonical",
+    "compression-dictionary",
+    "dns-prefetch",
+    "expect",
+    "external",
+    "help",
+    "icon",
+    "license",
+    "manifest",
+    "mask-icon",
+    "me",
+    "modulepreload",
+    "next",
+    "nofollow",
+    "noopener",
+    "noreferrer",
+    "opener",
+    "pingback",
+    "preconnect",
+    "prefetch",
+    "preload",
+    "prerender",
+    "prev",
+    "privacy-policy",
+    "search",
+    "shortcut",
+    "sponsored",
+    "stylesheet",
+    "tag",
+    "terms-of-service",
+    "ugc",
+    "webmention",
+];
+
+/// Returns the allowed values of the given attribute of the given element if it is enumerated.
+fn enumerated_values(tag: &str, name: &str) -> Option<&'static [&'static str]> {
+    Some(match (tag, name) {
+        (_, "autocapitalize") => &["none", "off", "sentences", "on", "words", "characters"],
+        (_, "contenteditable") => &["", "true", "false", "plaintext-only"],
+        (_, "dir") => &["ltr", "rtl", "auto"],
+        (_, "draggable") => &["true", "false"],
+        (_, "enterkeyhint") => &["enter", "done", "go", "next", "previous", "search", "send"],
+        (_, "inputmode") => &[
+            "none", "text", "decimal", "numeric", "tel", "search", "email", "url",
+        ],
+        (_, "popover") => &["", "auto", "manual", "hint"],
+        (_, "spellcheck") => &["", "true", "false"],
+        (_, "translate") => &["", "yes", "no"],
+
+        ("input", "type") => &[
+            "button",
+            "checkbox",
+            "color",
+            "date",
+            "datetime-local",
+            "email",
+            "file",
+            "hidden",
+            "image",
+            "month",
+            "number",
+            "password",
+            "radio",
+            "range",
+            "reset",
+            "search",
+            "submit",
+            "tel",
+            "text",
+            "time",
+            "url",
+            "week",
+        ],
+        ("button", "type") => &["submit", "reset", "button"],
+        ("a" | "area" | "form" | "link", "rel") => &LINK_TYPES,
+        ("img" | "iframe", "loading") => &["lazy", "eager"],
+        ("img", "decoding") => &["sync", "async", "auto"],
+        ("img" | "link" | "script", "fetchpriority") => &["high", "low", "auto"],
+        ("audio" | "img" | "link" | "script" | "video", "crossorigin") => {
+            &["", "anonymous", "use-credentials"]
+        }
+        ("a" | "area" | "iframe" | "img" | "link" | "script", "referrerpolicy") => {
+            &REFERRER_POLICIES
+        }
+        ("form", "method") | ("button" | "input", "formmethod") => &FORM_METHODS,
+        ("form", "enctype") | ("button" | "input", "formenctype") => &FORM_ENCTYPES,
+        ("audio" | "video", "preload") => &["", "none", "metadata", "auto"],
+        ("textarea", "wrap") => &["soft", "hard"],
+        ("track", "kind") => &[
+            "subtitles",
+            "captions",
+            "descriptions",
+            "chapters",
+            "metadata",
+        ],
+        ("th", "scope") => &["row", "col", "rowgroup", "colgroup"],
+        ("template", "shadowrootmode") => &["open", "closed"],
+        _ => return None,
+    })
+}
+
+/// Returns `true` if the value of the given attribute is a space-separated list of values.
+fn is_token_list(name: &str) -> bool {
+    name == "rel"
+}
+
+/// Returns `true` if attributes of the given element are checked.
+///
+/// Only attributes of known html elements are checked; custom and foreign elements can have any
+/// attributes.
+fn is_checked(element: &Element) -> bool {
+    !element.frag && !element.foreign && !element.tag.contains('-')
+}
+
+/// Warns if the given attribute name is not allowed on the given element.
+pub fn check_attribute_name(element: &Element, name: &str, node: &mut Node, span: Span) {
+    if node.unchecked != 0 || !is_checked(element) {
+        return;
+    }
+
+    let attrs = element_attrs(&element.tag);
+
+    if GLOBAL_ATTRS.contains(&name)
+        || attrs.contains(&name)
+        || is_prefixed(name, "data-")
+        || is_prefixed(name, "aria-")
+        || is_event_handler(name)
+    {
+        return;
+    }
+
+    let message = format!("unknown attribute `{}` for `<{}>`", name, element.tag);
+    let candidates = GLOBAL_ATTRS.iter().chain(attrs);
+
+    node.warn(
+        "unknown_attribute",
+        span,
+        with_suggestion(message, name, candidates),
+    );
+}
+
+/// Warns if the given literal value is not allowed for the given attribute if it is enumerated.
+pub fn check_attribute_value(
+    element: &Element,
+    name: &str,
+    value: &str,
+    node: &mut Node,
+    span: Span,
+) {
+    if node.unchecked != 0 || !is_checked(element) {
+        return;
+    }
+
+    let values = match enumerated_values(&element.tag, name) {
+        Some(values) => values,
+        None => return,
+    };
+
+    let mut check = |value: &str| {
+        if values.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+            return;
+        }
+
+        let message = format!(
+            "invalid value `{}` for attribute `{}` of `<{}>`",
+            value, name, element.tag
+        );
+
+        node.warn(
+            "invalid_attribute_value",
+            span,
+            with_suggestion(message, &value.to_ascii_lowercase(), values.iter()),
+        );
+    };
+
+    if is_token_list(name) {
+        value.split_ascii_whitespace().for_each(&mut check)
+    } else {
+        check(value)
+    }
+}
+
+fn is_prefixed(name: &str, prefix: &str) -> bool {
+    name.len() > prefix.len() && name.starts_with(prefix)
+}
+
+fn is_event_handler(name: &str) -> bool {
+    is_prefixed(name, "on") && name.bytes().all(|c| c.is_ascii_lowercase())
+}
+
+/// Appends the candidate most similar to `value` to the message, if any candidate is similar
+/// enough to be a likely typo.
+fn with_suggestion<'a>(
+    mut message: String,
+    value: &str,
+    candidates: impl Iterator<Item = &'a &'a str>,
+) -> String {
+    let threshold = (value.chars().count() / 3).max(1);
+    let suggestion = candidates
+        .filter(|candidate| !candidate.is_empty())
+        .map(|candidate| (edit_distance(value, candidate), candidate))
+        .filter(|&(distance, _)| distance <= threshold)
+        .min_by_key(|&(distance, _)| distance);
+
+    if let Some((_, candidate)) = suggestion {
+        message.push_str(&format!("; did you mean `{}`?", candidate));
+    }
+
+    message
+}
+
+/// Returns the optimal string alignment distance between two strings, which is the number of
+/// insertions, deletions, substitutions and transpositions of adjacent characters needed to turn
+/// one into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // distances[i][j] is the distance between a[..i] and b[..j]
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
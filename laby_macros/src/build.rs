@@ -7,17 +7,50 @@
 //   https://opensource.org/licenses/MIT
 //
 use crate::{
+    attribute::{check_attribute_name, check_attribute_value},
+    content::check_child,
+    delimited::{build_delimited, build_styles},
     get_element,
-    node::{Element, Node},
+    node::{xhtml_str, Element, Node},
+};
+use laby_common::{
+    internal::{
+        escape_script_code, is_safe_url, is_valid_custom_element_name, url_attribute_kind, Buffer,
+        EscapeContext, UrlKind, SAFE_URL_SCHEMES,
+    },
+    Render,
 };
-use laby_common::{internal::Buffer, Render};
 use proc_macro2::{Ident, Literal, TokenStream};
 use quote::quote;
 use syn::{
-    parse::Parser, punctuated::Punctuated, spanned::Spanned, token::Comma, Expr, ExprAssign,
-    ExprLit, Lit, Stmt,
+    parse::{ParseStream, Parser},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token::Comma,
+    Expr, ExprAssign, ExprLit, ExprRange, Lit, LitStr, Path, PathArguments, RangeLimits, Stmt,
+    Token,
 };
 
+/// Parses the arguments of `el!`, which are a custom element name followed by the usual
+/// arguments of an element macro.
+pub fn parse_custom_element(stream: TokenStream) -> syn::Result<(Element, TokenStream)> {
+    let parser = |input: ParseStream| {
+        let tag: LitStr = input.parse()?;
+
+        if !is_valid_custom_element_name(&tag.value()) {
+            return Err(syn::Error::new(tag.span(), "invalid custom element name"));
+        }
+
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok((Element::normal(tag.value()), input.parse()?))
+    };
+
+    Parser::parse2(parser, stream)
+}
+
 pub fn build_node(element: Element, stream: TokenStream) -> syn::Result<Node> {
     let mut node = Node::new(element.clone());
 
@@ -28,16 +61,24 @@ pub fn build_node(element: Element, stream: TokenStream) -> syn::Result<Node> {
 }
 
 fn build_inner(element: Element, stream: TokenStream, node: &mut Node) -> syn::Result<()> {
+    if element.frag && element.tag == "styles" {
+        return build_styles(stream, node);
+    }
+
+    if element.frag && !element.delimiter.is_empty() {
+        return build_delimited(element, stream, node);
+    }
+
     let args = Parser::parse2(Punctuated::<Expr, Comma>::parse_terminated, stream)?;
 
     let mut attrs = Vec::new();
     let mut children = Vec::new();
 
     for expr in args {
-        if let Expr::Assign(expr) = expr {
-            attrs.push(expr);
-        } else {
-            children.push(expr);
+        match expr {
+            Expr::Assign(_) => attrs.push(expr),
+            Expr::Range(ref range) if is_spread(range) => attrs.push(expr),
+            _ => children.push(expr),
         }
     }
 
@@ -59,7 +100,31 @@ fn build_inner(element: Element, stream: TokenStream, node: &mut Node) -> syn::R
         node.render.push_str("<");
         node.render.push_str(&element.tag);
 
+        if let Some((namespace, xhtml)) = element.namespace() {
+            if !attrs.iter().any(is_namespace_attr) {
+                let attr = format!(" xmlns=\"{}\"", namespace);
+
+                match xhtml {
+                    true => node.render.push_str_xhtml("", attr),
+                    false => node.render.push_str(attr),
+                }
+            }
+        }
+
         for attr in attrs {
+            let attr = match attr {
+                Expr::Assign(attr) => {
+                    check_attr(&element, &attr, node);
+                    attr
+                }
+                Expr::Range(range) => {
+                    set_spread_attrs(range, node)?;
+                    continue;
+                }
+
+                _ => unreachable!(),
+            };
+
             match *attr.right {
                 Expr::Macro(ref expr) => {
                     if let Some(ident) = expr.mac.path.get_ident() {
@@ -70,6 +135,14 @@ fn build_inner(element: Element, stream: TokenStream, node: &mut Node) -> syn::R
                             set_boolean_attr(attr, node)?;
                             continue;
                         }
+
+                        if ident == "opt" {
+                            let mut attr = attr.clone();
+                            attr.right = syn::parse2(expr.mac.tokens.clone())?;
+
+                            set_optional_attr(attr, node)?;
+                            continue;
+                        }
                     }
                 }
 
@@ -79,11 +152,32 @@ fn build_inner(element: Element, stream: TokenStream, node: &mut Node) -> syn::R
             set_attr(attr, node)?;
         }
 
-        node.render.push_str(">");
+        // foreign elements without children are closed in the start tag, as are void elements in
+        // xhtml, which must be well-formed xml
+        if element.foreign && children.is_empty() {
+            node.render.push_str("/>");
+            return Ok(());
+        }
+
+        match element.void {
+            true => node.render.push_str_xhtml(">", "/>"),
+            false => node.render.push_str(">"),
+        }
     }
 
     if !element.void {
         let mut separate = false;
+        let context = element
+            .context()
+            .filter(|_| !node.in_foreign())
+            .map(|context| node.switch_context(context));
+        let foreign = element
+            .foreign_content()
+            .map(|foreign| node.switch_foreign(foreign));
+
+        if !element.frag {
+            node.ancestors.push(element.clone());
+        }
 
         for child in children {
             if separate {
@@ -92,24 +186,19 @@ fn build_inner(element: Element, stream: TokenStream, node: &mut Node) -> syn::R
                 separate = true;
             }
 
-            match child {
-                Expr::Macro(ref expr) => {
-                    if let Some(ident) = expr.mac.path.get_ident() {
-                        if let Some(element) = get_element(ident.to_string()) {
-                            // flatten nested markup
-                            build_inner(element, expr.mac.tokens.clone(), node)?;
-                            continue;
-                        }
-                    }
-                }
+            build_child(child, node)?;
+        }
 
-                _ => {}
-            };
+        if let Some(foreign) = foreign {
+            node.restore_foreign(foreign);
+        }
 
-            set_child(child, node)?;
+        if let Some(context) = context {
+            node.restore_context(context);
         }
 
         if !element.frag {
+            node.ancestors.pop();
             node.render.push_str("</");
             node.render.push_str(&element.tag);
             node.render.push_str(">");
@@ -119,7 +208,114 @@ fn build_inner(element: Element, stream: TokenStream, node: &mut Node) -> syn::R
     Ok(())
 }
 
-fn try_unwrap_literal(expr: &Expr) -> Option<&ExprLit> {
+fn build_child(child: Expr, node: &mut Node) -> syn::Result<()> {
+    if let Expr::Macro(ref expr) = child {
+        if let Some(element) = get_nested_element(&expr.mac.path, node) {
+            check_child(&element, node, child.span())?;
+
+            // flatten nested markup
+            return build_inner(element, expr.mac.tokens.clone(), node);
+        }
+
+        if let Some(ident) = expr.mac.path.get_ident() {
+            let nested = if ident == "el" {
+                Some(parse_custom_element(expr.mac.tokens.clone())?)
+            } else {
+                None
+            };
+
+            if let Some((element, stream)) = nested {
+                check_child(&element, node, child.span())?;
+
+                // flatten nested markup
+                return build_inner(element, stream, node);
+            }
+
+            if ident == "unchecked" {
+                node.unchecked += 1;
+                build_child(syn::parse2(expr.mac.tokens.clone())?, node)?;
+                node.unchecked -= 1;
+
+                return Ok(());
+            }
+
+            if ident == "fallible" {
+                if node.render.is_async() {
+                    return Err(syn::Error::new(
+                        child.span(),
+                        "fallible children cannot be combined with awaited children",
+                    ));
+                }
+
+                let expr: Expr = syn::parse2(expr.mac.tokens.clone())?;
+                let value = node.store_fallible(quote!(#expr));
+
+                // the escaping context is restored when returning early from a raw text element or
+                // foreign content
+                node.render.push_expr(quote!(
+                    if let ::core::result::Result::Err(error) =
+                        ::laby::TryRender::try_render(#value, buffer)
+                    {
+                        buffer.set_context(context);
+                        buffer.set_foreign(foreign);
+                        return ::core::result::Result::Err(::core::convert::From::from(error));
+                    }
+                ));
+
+                return Ok(());
+            }
+
+            if ident == "awaited" {
+                if node.render.is_fallible() {
+                    return Err(syn::Error::new(
+                        child.span(),
+                        "awaited children cannot be combined with fallible children",
+                    ));
+                }
+
+                let expr: Expr = syn::parse2(expr.mac.tokens.clone())?;
+                let value = node.store_awaited(quote!(#expr));
+
+                node.render.push_await(value);
+                return Ok(());
+            }
+        }
+    }
+
+    set_child(child, node)
+}
+
+/// Returns the element of a nested element macro, such as `div!` or `svg::circle!`.
+///
+/// SVG and MathML elements have common names (e.g. `text!`) that may refer to unrelated macros,
+/// so they are only recognized without their module path inside an `<svg>` or `<math>` element.
+fn get_nested_element(path: &Path, node: &Node) -> Option<Element> {
+    let last = path.segments.last()?;
+    let modules = path.segments.len() - 1;
+    let name = last.ident.to_string();
+
+    if !matches!(last.arguments, PathArguments::None)
+        || !path
+            .segments
+            .iter()
+            .take(modules)
+            .all(|module| matches!(module.ident.to_string().as_str(), "laby" | "svg" | "mathml"))
+    {
+        return None;
+    }
+
+    let element = get_element(&name)?;
+
+    let qualified = modules != 0 || matches!(name.as_str(), "svg" | "math");
+
+    if element.foreign && !qualified && !node.in_foreign() {
+        return None;
+    }
+
+    Some(element)
+}
+
+pub fn try_unwrap_literal(expr: &Expr) -> Option<&ExprLit> {
     match expr {
         Expr::Lit(lit) if lit.attrs.len() == 0 => Some(lit),
         Expr::Paren(paren) if paren.attrs.len() == 0 => try_unwrap_literal(&paren.expr),
@@ -136,7 +332,7 @@ fn try_unwrap_literal(expr: &Expr) -> Option<&ExprLit> {
     }
 }
 
-fn try_unwrap_ident(expr: &Expr) -> Option<&Ident> {
+pub fn try_unwrap_ident(expr: &Expr) -> Option<&Ident> {
     match expr {
         Expr::Path(expr) if expr.attrs.len() == 0 => {
             if let Some(ident) = expr.path.get_ident() {
@@ -149,15 +345,48 @@ fn try_unwrap_ident(expr: &Expr) -> Option<&Ident> {
     }
 }
 
-fn try_render_literal(expr: &ExprLit) -> Option<String> {
+pub fn try_render_literal(expr: &ExprLit) -> Option<String> {
+    try_render_literal_after(expr, EscapeContext::Html, "")
+}
+
+/// Renders a literal in the given escaping context, as if `tail` was rendered immediately before
+/// it.
+///
+/// Literals that are rendered differently in xhtml are not rendered at compile time.
+pub fn try_render_literal_after(
+    expr: &ExprLit,
+    context: EscapeContext,
+    tail: &str,
+) -> Option<String> {
+    let html = try_render_literal_in(expr, context, tail, false)?;
+    let xhtml = try_render_literal_in(expr, context, "", true)?;
+
+    (html == xhtml).then_some(html)
+}
+
+/// Renders a literal in the given escaping context, in either html or xhtml.
+fn try_render_literal_in(
+    expr: &ExprLit,
+    context: EscapeContext,
+    tail: &str,
+    xhtml: bool,
+) -> Option<String> {
     macro_rules! render {
         ($expr:expr) => {{
-            let mut buffer = Buffer::new();
+            let mut buffer = Buffer::from(tail);
+            buffer.set_context(context);
+            buffer.set_xhtml(xhtml);
             Render::render($expr, &mut buffer);
-            buffer.into_string()
+            buffer.as_str()[tail.len()..].to_string()
         }};
     }
 
+    if let Some(code) = try_unwrap_script_code(expr, context).filter(|_| !xhtml) {
+        let mut buffer = Buffer::from(tail);
+        escape_script_code(&code, &mut buffer);
+        return Some(buffer.as_str()[tail.len()..].to_string());
+    }
+
     if expr.attrs.len() == 0 {
         Some(match expr.lit {
             Lit::Str(ref v) => render!(v.value()),
@@ -174,16 +403,24 @@ fn try_render_literal(expr: &ExprLit) -> Option<String> {
     }
 }
 
+/// Returns the value of a string or character literal in a `<script>` element, which is code
+/// rather than data and is therefore not escaped as a javascript string.
+fn try_unwrap_script_code(expr: &ExprLit, context: EscapeContext) -> Option<String> {
+    match expr.lit {
+        _ if context != EscapeContext::Script || !expr.attrs.is_empty() => None,
+        Lit::Str(ref v) => Some(v.value()),
+        Lit::Char(ref v) => Some(v.value().to_string()),
+        _ => None,
+    }
+}
+
 fn set_attr(assign: ExprAssign, node: &mut Node) -> syn::Result<()> {
     let left = assign.left;
     let right = assign.right;
 
     node.render.push_str(" ");
 
-    if let Some(value) = try_unwrap_literal(&left)
-        .and_then(try_render_literal)
-        .or_else(|| try_unwrap_ident(&left).map(ToString::to_string))
-    {
+    if let Some(value) = get_attr_name(&left) {
         node.render.push_str(value);
     } else {
         let value = node.store_generic(quote!(#left), quote!(::laby::Render));
@@ -192,11 +429,14 @@ fn set_attr(assign: ExprAssign, node: &mut Node) -> syn::Result<()> {
     }
 
     node.render.push_str("=\"");
+    check_url_literal(&left, &right)?;
 
     if let Some(value) = try_unwrap_literal(&right).and_then(try_render_literal) {
         node.render.push_str(value);
     } else {
         let value = node.store_generic(quote!(#right), quote!(::laby::Render));
+        let value = sanitize_url(&left, &right, quote!(#value));
+
         node.render
             .push_expr(quote!(::laby::Render::render(#value, buffer)));
     }
@@ -205,6 +445,135 @@ fn set_attr(assign: ExprAssign, node: &mut Node) -> syn::Result<()> {
     Ok(())
 }
 
+/// Wraps the value of an attribute for sanitization if it is a dynamic url.
+fn sanitize_url(name: &Expr, expr: &Expr, value: TokenStream) -> TokenStream {
+    // values explicitly marked as raw are trusted
+    match get_static_name(name) {
+        Some(name) if !is_raw_macro(expr) => match url_attribute_kind(&name) {
+            Some(UrlKind::Url) => quote!(::laby::RenderUrl(#value)),
+            Some(UrlKind::Srcset) => quote!(::laby::RenderSrcset(#value)),
+            None => value,
+        },
+        _ => value,
+    }
+}
+
+/// Rejects literal values of URL attributes with unsafe schemes, which are not sanitized at
+/// runtime.
+fn check_url_literal(name: &Expr, expr: &Expr) -> syn::Result<()> {
+    let value = match try_unwrap_literal(expr) {
+        Some(ExprLit {
+            lit: Lit::Str(value),
+            ..
+        }) => value.value(),
+        _ => return Ok(()),
+    };
+
+    let safe = match get_static_name(name).and_then(|name| url_attribute_kind(&name)) {
+        Some(UrlKind::Url) => is_safe_url(&value),
+
+        // the url of each image candidate is followed by optional descriptors
+        Some(UrlKind::Srcset) => value.split(',').all(|candidate| {
+            is_safe_url(
+                candidate
+                    .split_ascii_whitespace()
+                    .next()
+                    .unwrap_or_default(),
+            )
+        }),
+
+        None => true,
+    };
+
+    if safe {
+        Ok(())
+    } else {
+        Err(syn::Error::new(
+            expr.span(),
+            format!(
+                "unsafe url; only relative urls and the schemes {} are allowed \
+                (wrap the value in `raw!` to render it verbatim)",
+                SAFE_URL_SCHEMES.join(", ")
+            ),
+        ))
+    }
+}
+
+fn get_static_name(expr: &Expr) -> Option<String> {
+    match try_unwrap_literal(expr) {
+        Some(ExprLit {
+            lit: Lit::Str(value),
+            ..
+        }) => Some(value.value()),
+        _ => try_unwrap_ident(expr).map(get_ident_name),
+    }
+}
+
+/// Returns the rendered name of an attribute if it is known at compile time.
+fn get_attr_name(expr: &Expr) -> Option<String> {
+    try_unwrap_literal(expr)
+        .and_then(try_render_literal)
+        .or_else(|| try_unwrap_ident(expr).map(get_ident_name))
+}
+
+/// Returns the name of an attribute written as an identifier, without the `r#` prefix of raw
+/// identifiers (e.g. `r#type`) and with underscores replaced with hyphens (e.g. `data_id`).
+fn get_ident_name(ident: &Ident) -> String {
+    let name = ident.to_string();
+    let name = name.strip_prefix("r#").unwrap_or(&name);
+
+    name.replace('_', "-")
+}
+
+/// Returns a boolean attribute with the given name, preceded by a space, in html and xhtml.
+///
+/// XML does not allow attributes without values, so the name is repeated as the value of the
+/// attribute in xhtml (e.g. `disabled="disabled"`).
+fn get_boolean_attr(name: &str) -> (String, String) {
+    (format!(" {}", name), format!(" {}=\"{}\"", name, name))
+}
+
+/// Validates the name of an attribute written as an identifier, and its value if it is a literal.
+///
+/// Attribute names written as string literals are not validated.
+fn check_attr(element: &Element, assign: &ExprAssign, node: &mut Node) {
+    let name = match try_unwrap_ident(&assign.left) {
+        Some(ident) => get_ident_name(ident),
+        None => return,
+    };
+
+    check_attribute_name(element, &name, node, assign.left.span());
+
+    if let Some(ExprLit {
+        lit: Lit::Str(value),
+        ..
+    }) = try_unwrap_literal(&assign.right)
+    {
+        check_attribute_value(element, &name, &value.value(), node, assign.right.span());
+    }
+}
+
+/// Returns `true` if the given attribute is an explicit `xmlns` attribute.
+fn is_namespace_attr(attr: &Expr) -> bool {
+    match attr {
+        Expr::Assign(assign) => get_static_name(&assign.left).as_deref() == Some("xmlns"),
+        _ => false,
+    }
+}
+
+fn is_raw_macro(expr: &Expr) -> bool {
+    match expr {
+        // also matches paths like `laby::raw`
+        Expr::Macro(expr) => expr
+            .mac
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "raw"),
+        _ => false,
+    }
+}
+
 fn set_boolean_attr(assign: ExprAssign, node: &mut Node) -> syn::Result<()> {
     let left = assign.left;
     let right = assign.right;
@@ -214,27 +583,22 @@ fn set_boolean_attr(assign: ExprAssign, node: &mut Node) -> syn::Result<()> {
         _ => None,
     }) {
         if enabled {
-            node.render.push_str(" ");
-
-            if let Some(value) = try_unwrap_literal(&left)
-                .and_then(try_render_literal)
-                .or_else(|| try_unwrap_ident(&left).map(ToString::to_string))
-            {
-                node.render.push_str(value);
+            if let Some(value) = get_attr_name(&left) {
+                let (html, xhtml) = get_boolean_attr(&value);
+                node.render.push_str_xhtml(html, xhtml);
             } else {
                 let value = node.store_generic(quote!(#left), quote!(::laby::Render));
-                node.render
-                    .push_expr(quote!(::laby::Render::render(#value, buffer)));
+                node.render.push_expr(quote!(
+                    ::laby::internal::render_boolean_attr(#value, buffer)
+                ));
             }
         }
     } else {
         let enabled = node.store_concrete(quote!(#right), quote!(::core::primitive::bool));
 
-        if let Some(value) = try_unwrap_literal(&left)
-            .and_then(try_render_literal)
-            .or_else(|| try_unwrap_ident(&left).map(ToString::to_string))
-        {
-            let value = Literal::string(&format!(" {}", value));
+        if let Some(value) = get_attr_name(&left) {
+            let (html, xhtml) = get_boolean_attr(&value);
+            let value = xhtml_str(&html, &xhtml);
             node.render.push_expr(quote!(
                 if #enabled {
                     buffer.push_str(#value);
@@ -244,8 +608,7 @@ fn set_boolean_attr(assign: ExprAssign, node: &mut Node) -> syn::Result<()> {
             let value = node.store_generic(quote!(#left), quote!(::laby::Render));
             node.render.push_expr(quote!(
                 if #enabled {
-                    buffer.push(' ');
-                    ::laby::Render::render(#value, buffer);
+                    ::laby::internal::render_boolean_attr(#value, buffer);
                 }
             ));
         }
@@ -254,9 +617,86 @@ fn set_boolean_attr(assign: ExprAssign, node: &mut Node) -> syn::Result<()> {
     Ok(())
 }
 
+fn set_optional_attr(assign: ExprAssign, node: &mut Node) -> syn::Result<()> {
+    let left = assign.left;
+    let right = assign.right;
+
+    let option = node.store_optional(quote!(#right), quote!(::laby::Render));
+    let value = sanitize_url(&left, &right, quote!(value));
+
+    let name = if let Some(value) = get_attr_name(&left) {
+        let value = Literal::string(&format!(" {}=\"", value));
+        quote!(buffer.push_str(#value);)
+    } else {
+        let value = node.store_generic(quote!(#left), quote!(::laby::Render));
+        quote!(
+            buffer.push(' ');
+            ::laby::Render::render(#value, buffer);
+            buffer.push_str("=\"");
+        )
+    };
+
+    node.render.push_expr(quote!(
+        if let ::core::option::Option::Some(value) = #option {
+            #name
+            ::laby::Render::render(#value, buffer);
+            buffer.push('"');
+        }
+    ));
+
+    Ok(())
+}
+
+/// Returns `true` if the given range expression is an attribute spread like `..attrs`.
+fn is_spread(range: &ExprRange) -> bool {
+    range.attrs.is_empty()
+        && range.from.is_none()
+        && range.to.is_some()
+        && matches!(range.limits, RangeLimits::HalfOpen(_))
+}
+
+fn set_spread_attrs(range: ExprRange, node: &mut Node) -> syn::Result<()> {
+    let expr = range.to;
+    let value = node.store_generic(
+        quote!(::laby::RenderAttributes(#expr)),
+        quote!(::laby::Render),
+    );
+
+    node.render
+        .push_expr(quote!(::laby::Render::render(#value, buffer)));
+
+    Ok(())
+}
+
+/// Renders a child literal at compile time if possible, in html and xhtml.
+pub fn try_render_child_literal(expr: &Expr, node: &Node) -> Option<(String, String)> {
+    // literals in raw text elements can only be rendered at compile time if the contents rendered
+    // before them are known, because escaping depends on them
+    let tail = match node.context {
+        EscapeContext::Html | EscapeContext::CssValue => "",
+        _ if node.static_tail => node.render.pending(),
+        _ => return None,
+    };
+
+    try_unwrap_literal(expr).and_then(|expr| {
+        Some((
+            try_render_literal_in(expr, node.context, tail, false)?,
+            try_render_literal_in(expr, node.context, "", true)?,
+        ))
+    })
+}
+
 fn set_child(expr: Expr, node: &mut Node) -> syn::Result<()> {
-    if let Some(value) = try_unwrap_literal(&expr).and_then(try_render_literal) {
-        node.render.push_str(value);
+    if let Some((html, xhtml)) = try_render_child_literal(&expr, node) {
+        node.render.push_str_xhtml(html, xhtml);
+    } else if let Some(code) =
+        try_unwrap_literal(&expr).and_then(|expr| try_unwrap_script_code(expr, node.context))
+    {
+        node.render.push_expr(quote!(if buffer.xhtml() {
+            ::laby::internal::escape_xml(#code, buffer)
+        } else {
+            ::laby::internal::escape_script_code(#code, buffer)
+        }));
     } else {
         let value = node.store_generic(quote!(#expr), quote!(::laby::Render));
         node.render
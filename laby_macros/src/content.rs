@@ -0,0 +1,193 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use crate::node::{Element, Node};
+use proc_macro2::Span;
+
+/// Elements that are not phrasing content, which would implicitly close a `<p>` element.
+const FLOW_TAGS: [&str; 35] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "caption",
+    "dd",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "li",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+];
+
+/// Elements whose content model is phrasing content.
+const PHRASING_PARENT_TAGS: [&str; 32] = [
+    "abbr", "b", "bdi", "bdo", "button", "cite", "code", "data", "dfn", "em", "h1", "h2", "h3",
+    "h4", "h5", "h6", "i", "kbd", "label", "legend", "mark", "p", "pre", "q", "s", "samp", "small",
+    "span", "strong", "sub", "sup", "time",
+];
+
+/// Elements that can be children of any element, including those with restricted content.
+const SCRIPT_SUPPORTING_TAGS: [&str; 2] = ["script", "template"];
+
+/// Interactive elements, which cannot be nested in other interactive elements.
+const INTERACTIVE_TAGS: [&str; 9] = [
+    "a", "button", "details", "embed", "iframe", "label", "select", "textarea", "input",
+];
+
+/// Returns the elements the given element must be a child of, if restricted.
+fn required_parents(tag: &str) -> Option<&'static [&'static str]> {
+    Some(match tag {
+        "li" => &["ul", "ol", "menu"],
+        "dt" | "dd" => &["dl", "div"],
+        "tr" => &["thead", "tbody", "tfoot"],
+        "td" | "th" => &["tr"],
+        "thead" | "tbody" | "tfoot" | "caption" | "colgroup" => &["table"],
+        "col" => &["colgroup"],
+        "option" => &["select", "datalist", "optgroup"],
+        "optgroup" => &["select"],
+        "legend" => &["fieldset"],
+        "figcaption" => &["figure"],
+        "summary" => &["details"],
+        "source" => &["audio", "video", "picture"],
+        "track" => &["audio", "video"],
+        "param" => &["object"],
+        "rb" | "rp" | "rt" | "rtc" => &["ruby", "rtc"],
+        "head" | "body" => &["html"],
+        "html" => &[],
+        _ => return None,
+    })
+}
+
+/// Returns the elements the given element can contain, if restricted.
+fn allowed_children(tag: &str) -> Option<&'static [&'static str]> {
+    Some(match tag {
+        "ul" | "ol" | "menu" => &["li"],
+        "dl" => &["dt", "dd", "div"],
+        "table" => &["caption", "colgroup", "thead", "tbody", "tfoot"],
+        "thead" | "tbody" | "tfoot" => &["tr"],
+        "tr" => &["td", "th"],
+        "select" => &["option", "optgroup", "hr"],
+        "optgroup" => &["option"],
+        "colgroup" => &["col"],
+        "html" => &["head", "body"],
+        _ => return None,
+    })
+}
+
+/// Returns the elements that cannot be descendants of the given element.
+fn forbidden_descendants(tag: &str) -> &'static [&'static str] {
+    match tag {
+        "a" | "button" => &INTERACTIVE_TAGS,
+        "form" => &["form"],
+        "label" => &["label"],
+        "header" | "footer" => &["header", "footer"],
+        "dfn" => &["dfn"],
+        "meter" => &["meter"],
+        "progress" => &["progress"],
+        _ => &[],
+    }
+}
+
+/// Checks whether an element can be nested statically at the current position of the given node,
+/// according to the HTML content model.
+pub fn check_child(child: &Element, node: &Node, span: Span) -> syn::Result<()> {
+    // foreign elements can be nested anywhere phrasing content is allowed
+    if child.frag || child.foreign || node.unchecked != 0 {
+        return Ok(());
+    }
+
+    let tag = child.tag.as_str();
+    let parent = match node.ancestors.last() {
+        // the contents of foreign elements and templates are not checked
+        Some(parent) if !parent.foreign && parent.tag != "template" => parent.tag.as_str(),
+        _ => return Ok(()),
+    };
+
+    let error = |message: String| Err(syn::Error::new(span, message));
+
+    if let Some(ancestor) = node
+        .ancestors
+        .iter()
+        .rev()
+        .take_while(|ancestor| !ancestor.foreign && ancestor.tag != "template")
+        .find(|ancestor| forbidden_descendants(&ancestor.tag).contains(&tag))
+    {
+        return error(format!(
+            "`<{}>` cannot be a descendant of `<{}>`",
+            tag, ancestor.tag
+        ));
+    }
+
+    if tag == "tr" && parent == "table" {
+        return error("`<tr>` cannot be a child of `<table>`; wrap it in `<tbody>`".into());
+    }
+
+    if let Some(parents) = required_parents(tag) {
+        if parents.is_empty() {
+            return error(format!("`<{}>` cannot be a child of `<{}>`", tag, parent));
+        }
+
+        if !parents.contains(&parent) {
+            return error(format!(
+                "`<{}>` must be a child of {}",
+                tag,
+                join_tags(parents)
+            ));
+        }
+    }
+
+    if let Some(children) = allowed_children(parent) {
+        if !children.contains(&tag) && !SCRIPT_SUPPORTING_TAGS.contains(&tag) {
+            return error(format!(
+                "`<{}>` cannot be a child of `<{}>`; expected {}",
+                tag,
+                parent,
+                join_tags(children)
+            ));
+        }
+    }
+
+    if PHRASING_PARENT_TAGS.contains(&parent) && FLOW_TAGS.contains(&tag) {
+        return error(format!("`<{}>` cannot be a child of `<{}>`", tag, parent));
+    }
+
+    Ok(())
+}
+
+fn join_tags(tags: &[&str]) -> String {
+    let tags: Vec<_> = tags.iter().map(|tag| format!("`<{}>`", tag)).collect();
+
+    match tags.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+        _ => tags.concat(),
+    }
+}
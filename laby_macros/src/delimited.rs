@@ -0,0 +1,344 @@
+//
+// Copyright (c) 2021 chiya.dev
+//
+// Use of this source code is governed by the MIT License
+// which can be found in the LICENSE file and at:
+//
+//   https://opensource.org/licenses/MIT
+//
+use crate::{
+    build::{
+        try_render_child_literal, try_render_literal_after, try_unwrap_ident, try_unwrap_literal,
+    },
+    node::{Element, Node},
+};
+use laby_common::internal::EscapeContext;
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token::Comma,
+    Expr, Lit, Token,
+};
+
+/// Item of a delimited fragment, optionally followed by a condition (e.g. `"active" => active`).
+struct DelimitedItem {
+    expr: Expr,
+    cond: Option<Expr>,
+}
+
+impl Parse for DelimitedItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let expr = input.parse()?;
+        let cond = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Self { expr, cond })
+    }
+}
+
+/// Builds a fragment that places its delimiter only between items that render something.
+pub fn build_delimited(element: Element, stream: TokenStream, node: &mut Node) -> syn::Result<()> {
+    let items = Parser::parse2(Punctuated::<DelimitedItem, Comma>::parse_terminated, stream)?;
+    let mut delimited = Delimited::new(element.delimiter);
+
+    for item in items {
+        if let Expr::Assign(_) | Expr::Range(_) = item.expr {
+            return Err(syn::Error::new(
+                item.expr.span(),
+                "invalid attribute in fragment",
+            ));
+        }
+
+        // literals that are rendered differently in xhtml are rendered at runtime
+        let value = try_render_child_literal(&item.expr, node)
+            .and_then(|(html, xhtml)| (html == xhtml).then_some(html))
+            .ok_or(item.expr);
+        delimited.push(node, "", value, item.cond);
+    }
+
+    delimited.finish(node);
+    Ok(())
+}
+
+/// Builds a list of css declarations, omitting declarations whose values render nothing.
+pub fn build_styles(stream: TokenStream, node: &mut Node) -> syn::Result<()> {
+    let items = Parser::parse2(Punctuated::<DelimitedItem, Comma>::parse_terminated, stream)?;
+    let mut decls = Vec::new();
+
+    for item in items {
+        let decl = match item.expr {
+            Expr::Assign(decl) => decl,
+            expr => {
+                return Err(syn::Error::new(
+                    expr.span(),
+                    "expected css declaration `property = value`",
+                ))
+            }
+        };
+
+        let name = get_property_name(&decl.left)?;
+        let value = try_unwrap_literal(&decl.right)
+            .and_then(|expr| try_render_literal_after(expr, EscapeContext::CssValue, ""))
+            .ok_or(*decl.right);
+
+        decls.push((name, value, item.cond));
+    }
+
+    // values are only escaped at runtime if there are any non-literal values
+    let context = match decls.iter().any(|(_, value, _)| value.is_err()) {
+        true => Some(node.switch_context(EscapeContext::CssValue)),
+        false => None,
+    };
+
+    let mut delimited = Delimited::new(";");
+
+    for (name, value, cond) in decls {
+        delimited.push(node, &format!("{}:", name), value, cond);
+    }
+
+    delimited.finish(node);
+
+    if let Some(context) = context {
+        node.restore_context(context);
+    }
+
+    Ok(())
+}
+
+/// Returns the css property name of the left side of a declaration.
+///
+/// Underscores in identifiers are replaced with hyphens (e.g. `font_size` becomes `font-size`).
+/// String literals are used as-is, which allows custom properties like `"--main-color"`.
+fn get_property_name(expr: &Expr) -> syn::Result<String> {
+    let name = match try_unwrap_literal(expr) {
+        Some(lit) => match lit.lit {
+            Lit::Str(ref value) => Some(value.value()),
+            _ => None,
+        },
+
+        None => try_unwrap_ident(expr).map(|ident| {
+            let name = ident.to_string();
+            name.strip_prefix("r#").unwrap_or(&name).replace('_', "-")
+        }),
+    };
+
+    match name {
+        Some(name) if is_valid_property_name(&name) => Ok(name),
+        _ => Err(syn::Error::new(expr.span(), "invalid css property name")),
+    }
+}
+
+fn is_valid_property_name(name: &str) -> bool {
+    let body = name.strip_prefix('-').unwrap_or(name);
+
+    !body.is_empty()
+        && !body.starts_with(|c: char| c.is_ascii_digit())
+        && body
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Generates code that renders items with a delimiter only between the items that render
+/// something.
+///
+/// Whether anything has been rendered yet is tracked at compile time for as long as it is known,
+/// and by a local variable at runtime afterwards. Items are collected first and generated by
+/// [`finish`](Self::finish), so that the variable is only declared and assigned if it is read by
+/// a later item.
+struct Delimited {
+    delimiter: String,
+    rendered: Option<bool>,
+    ops: Vec<Op>,
+}
+
+enum Op {
+    /// Static markup.
+    Static(String),
+
+    /// Statement that does not depend on what was rendered before it.
+    Stmt(TokenStream, Option<Ident>),
+
+    /// Statement that renders an item preceded by `first` if nothing was rendered before it, or
+    /// by `rest` otherwise, as tracked by the runtime flag.
+    Flagged {
+        first: String,
+        rest: String,
+        value: Result<String, Ident>,
+        cond: Option<Ident>,
+    },
+}
+
+impl Delimited {
+    fn new(delimiter: impl Into<String>) -> Self {
+        Self {
+            delimiter: delimiter.into(),
+            rendered: Some(false),
+            ops: Vec::new(),
+        }
+    }
+
+    /// Renders an item preceded by `prefix`, and preceded by the delimiter if anything was
+    /// rendered before it. The prefix is omitted along with the item if the item renders nothing.
+    ///
+    /// `value` is the item rendered at compile time, or the expression to render at runtime.
+    fn push(
+        &mut self,
+        node: &mut Node,
+        prefix: &str,
+        value: Result<String, Expr>,
+        cond: Option<Expr>,
+    ) {
+        if let Ok(ref value) = value {
+            if value.is_empty() {
+                return;
+            }
+        }
+
+        if self.rendered == Some(false) && (value.is_err() || cond.is_some()) {
+            self.rendered = None;
+        }
+
+        let first = prefix.to_string();
+        let rest = format!("{}{}", self.delimiter, prefix);
+
+        let cond =
+            cond.map(|cond| node.store_concrete(quote!(#cond), quote!(::core::primitive::bool)));
+
+        let value = value.map_err(|expr| node.store_generic(quote!(#expr), quote!(::laby::Render)));
+
+        match (value, self.rendered) {
+            (Ok(value), Some(known)) => {
+                let value = format!("{}{}", if known { rest } else { first }, value);
+
+                match cond {
+                    None => {
+                        self.ops.push(Op::Static(value));
+                        self.rendered = Some(true);
+                    }
+
+                    Some(cond) => {
+                        let value = Literal::string(&value);
+                        self.ops
+                            .push(Op::Stmt(quote!(buffer.push_str(#value);), Some(cond)));
+                    }
+                }
+            }
+
+            (Err(value), Some(_)) => {
+                let rest = Literal::string(&rest);
+                let stmt = quote!(::laby::internal::render_delimited(#value, #rest, buffer););
+
+                self.ops.push(Op::Stmt(stmt, cond));
+            }
+
+            (value, None) => {
+                // unconditional literals always render something
+                if value.is_ok() && cond.is_none() {
+                    self.rendered = Some(true);
+                }
+
+                self.ops.push(Op::Flagged {
+                    first,
+                    rest,
+                    value,
+                    cond,
+                });
+            }
+        }
+    }
+
+    /// Generates the collected items.
+    fn finish(self, node: &mut Node) {
+        let flagged = self
+            .ops
+            .iter()
+            .filter(|op| matches!(op, Op::Flagged { .. }))
+            .count();
+
+        // nothing is rendered before the first flagged item, so the flag is only needed if there
+        // are more of them
+        let flag = if flagged > 1 {
+            Some(node.local("d"))
+        } else {
+            None
+        };
+
+        let mut index = 0;
+
+        for op in self.ops {
+            let (stmt, cond) = match op {
+                Op::Static(value) => {
+                    node.render.push_str(value);
+                    continue;
+                }
+
+                Op::Stmt(stmt, cond) => (stmt, cond),
+
+                Op::Flagged {
+                    first,
+                    rest,
+                    value,
+                    cond,
+                } => {
+                    let (read, assign) = (index != 0, index + 1 != flagged);
+                    index += 1;
+
+                    if !read && assign {
+                        node.render.push_local(quote!(let mut #flag = false));
+                    }
+
+                    let prefix = match read {
+                        true => {
+                            let (first, rest) = (Literal::string(&first), Literal::string(&rest));
+                            quote!(if #flag { #rest } else { #first })
+                        }
+                        false => {
+                            let first = Literal::string(&first);
+                            quote!(#first)
+                        }
+                    };
+
+                    let assign = match assign {
+                        true => quote!(#flag = true;),
+                        false => quote!(),
+                    };
+
+                    let stmt = match value {
+                        Ok(value) => {
+                            let value = Literal::string(&value);
+                            quote!(
+                                buffer.push_str(#prefix);
+                                buffer.push_str(#value);
+                                #assign
+                            )
+                        }
+
+                        Err(value) if assign.is_empty() => quote!(
+                            ::laby::internal::render_delimited(#value, #prefix, buffer);
+                        ),
+
+                        Err(value) => quote!(
+                            if ::laby::internal::render_delimited(#value, #prefix, buffer) {
+                                #assign
+                            }
+                        ),
+                    };
+
+                    (stmt, cond)
+                }
+            };
+
+            match cond {
+                Some(cond) => node.render.push_expr(quote!(if #cond { #stmt })),
+                None => node.render.push_expr(quote!({ #stmt })),
+            }
+        }
+    }
+}
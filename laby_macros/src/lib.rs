@@ -12,13 +12,17 @@
 //! crate directly.
 extern crate proc_macro;
 
+use build::parse_custom_element;
 use component::{generate_component_macro, generate_component_macro_call};
 use matching::generate_frag_match;
 use node::{Element, Node};
 use proc_macro::{Group, Span, TokenStream, TokenTree};
 
+mod attribute;
 mod build;
 mod component;
+mod content;
+mod delimited;
 mod matching;
 mod node;
 
@@ -70,12 +74,145 @@ declare_tags![
     [area, base, br, col, embed, hr, img, input, link, meta, param, source, track, wbr]
 ];
 
+macro_rules! declare_foreign_tags {
+    ($type:ident, $url:literal, [$list:ident, $count:literal], [$($(#[$attr:meta])* $name:ident),*]) => {
+        const $list: [&str; $count] = [$(stringify!($name)),*];
+
+        $(
+            declare_tag!(
+                $(#[$attr])*
+                #[doc = concat!("[`<", stringify!($name), ">`](", $url, stringify!($name), ") element.")]
+                #[allow(non_snake_case)]
+                $name,
+                Element::$type(stringify!($name))
+            );
+        )*
+    };
+}
+
+// svg elements that share their names with html elements (a, script, style, title) are rendered
+// using the html macros
+declare_foreign_tags![
+    foreign,
+    "https://developer.mozilla.org/en-US/docs/Web/SVG/Element/",
+    [KNOWN_SVG_TAGS, 58],
+    [
+        animate,
+        animateMotion,
+        animateTransform,
+        circle,
+        clipPath,
+        defs,
+        desc,
+        ellipse,
+        feBlend,
+        feColorMatrix,
+        feComponentTransfer,
+        feComposite,
+        feConvolveMatrix,
+        feDiffuseLighting,
+        feDisplacementMap,
+        feDistantLight,
+        feDropShadow,
+        feFlood,
+        feFuncA,
+        feFuncB,
+        feFuncG,
+        feFuncR,
+        feGaussianBlur,
+        feImage,
+        feMerge,
+        feMergeNode,
+        feMorphology,
+        feOffset,
+        fePointLight,
+        feSpecularLighting,
+        feSpotLight,
+        feTile,
+        feTurbulence,
+        filter,
+        foreignObject,
+        g,
+        image,
+        line,
+        linearGradient,
+        marker,
+        mask,
+        metadata,
+        mpath,
+        path,
+        pattern,
+        polygon,
+        polyline,
+        radialGradient,
+        rect,
+        set,
+        stop,
+        svg,
+        switch,
+        symbol,
+        text,
+        textPath,
+        tspan,
+        view
+    ]
+];
+
+declare_foreign_tags![
+    foreign,
+    "https://developer.mozilla.org/en-US/docs/Web/MathML/Element/",
+    [KNOWN_MATHML_TAGS, 29],
+    [
+        annotation,
+        math,
+        menclose,
+        merror,
+        mfrac,
+        mi,
+        mmultiscripts,
+        mn,
+        mo,
+        mover,
+        mpadded,
+        mphantom,
+        mprescripts,
+        mroot,
+        mrow,
+        ms,
+        mspace,
+        msqrt,
+        mstyle,
+        msub,
+        msubsup,
+        msup,
+        mtable,
+        mtd,
+        mtext,
+        mtr,
+        munder,
+        munderover,
+        semantics
+    ]
+];
+
+declare_tag!(
+    /// [`<use>`](https://developer.mozilla.org/en-US/docs/Web/SVG/Element/use) element.
+    ///
+    /// This macro must be invoked as `r#use!` because `use` is a keyword.
+    r#use,
+    Element::foreign("use")
+);
+
 declare_tag!(frag, Element::frag());
 declare_tag!(classes, Element::frag_with_delimiter(' '));
+declare_tag!(styles, Element::styles());
 
 fn get_element(tag: impl AsRef<str>) -> Option<Element> {
     let tag = tag.as_ref();
 
+    // raw identifiers like `r#use`
+    let tag = tag.strip_prefix("r#").unwrap_or(tag);
+
     if KNOWN_NORMAL_TAGS.contains(&tag) {
         return Some(Element::normal(tag));
     }
@@ -84,6 +221,10 @@ fn get_element(tag: impl AsRef<str>) -> Option<Element> {
         return Some(Element::void(tag));
     }
 
+    if KNOWN_SVG_TAGS.contains(&tag) || KNOWN_MATHML_TAGS.contains(&tag) || tag == "use" {
+        return Some(Element::foreign(tag));
+    }
+
     if tag == "frag" {
         return Some(Element::frag());
     }
@@ -92,9 +233,21 @@ fn get_element(tag: impl AsRef<str>) -> Option<Element> {
         return Some(Element::frag_with_delimiter(' '));
     }
 
+    if tag == "styles" {
+        return Some(Element::styles());
+    }
+
     None
 }
 
+#[proc_macro]
+pub fn el(stream: TokenStream) -> TokenStream {
+    match parse_custom_element(stream.into()) {
+        Ok((element, stream)) => Node::generate(element, stream).into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
 #[proc_macro_attribute]
 pub fn laby(args: TokenStream, stream: TokenStream) -> TokenStream {
     match generate_component_macro(stream.into(), args.into()) {
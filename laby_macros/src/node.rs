@@ -7,8 +7,9 @@
 //   https://opensource.org/licenses/MIT
 //
 use crate::build::build_node;
-use proc_macro2::{Literal, TokenStream};
-use quote::{format_ident, quote, ToTokens};
+use laby_common::internal::EscapeContext;
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
     punctuated::Punctuated,
     token::{Comma, Semi},
@@ -20,6 +21,7 @@ pub struct Element {
     pub tag: String,
     pub void: bool,
     pub frag: bool,
+    pub foreign: bool,
     pub delimiter: String,
 }
 
@@ -29,6 +31,7 @@ impl Element {
             tag: tag.as_ref().into(),
             void: false,
             frag: false,
+            foreign: false,
             delimiter: String::new(),
         }
     }
@@ -38,6 +41,18 @@ impl Element {
             tag: tag.as_ref().into(),
             void: true,
             frag: false,
+            foreign: false,
+            delimiter: String::new(),
+        }
+    }
+
+    /// Element in a foreign namespace (svg or mathml), which is self-closing when it has no children.
+    pub fn foreign(tag: impl AsRef<str>) -> Self {
+        Self {
+            tag: tag.as_ref().into(),
+            void: false,
+            frag: false,
+            foreign: true,
             delimiter: String::new(),
         }
     }
@@ -47,6 +62,7 @@ impl Element {
             tag: "frag".into(),
             void: false,
             frag: true,
+            foreign: false,
             delimiter: String::new(),
         }
     }
@@ -56,13 +72,73 @@ impl Element {
             tag: "frag".into(),
             void: false,
             frag: true,
+            foreign: false,
             delimiter: del.into(),
         }
     }
+
+    pub fn styles() -> Self {
+        Self {
+            tag: "styles".into(),
+            void: false,
+            frag: true,
+            foreign: false,
+            delimiter: ";".into(),
+        }
+    }
+
+    /// Returns the default `xmlns` attribute of this element if it is the root of a foreign
+    /// namespace, or the root of an xhtml document.
+    ///
+    /// The second value is `true` if the attribute is only rendered in xhtml.
+    pub fn namespace(&self) -> Option<(&'static str, bool)> {
+        match self.tag.as_str() {
+            "html" if !self.foreign => Some(("http://www.w3.org/1999/xhtml", true)),
+            _ if !self.foreign => None,
+            "svg" => Some(("http://www.w3.org/2000/svg", false)),
+            "math" => Some(("http://www.w3.org/1998/Math/MathML", false)),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the children of this element are foreign content if this element changes it,
+    /// i.e. if it is the root of a foreign namespace or an svg `<foreignObject>` containing html.
+    pub fn foreign_content(&self) -> Option<bool> {
+        match self.tag.as_str() {
+            _ if !self.foreign => None,
+            "svg" | "math" => Some(true),
+            "foreignObject" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Returns the escaping context of the children of this element if it is a raw text element.
+    ///
+    /// In xhtml and in foreign content, raw text elements do not exist, so
+    /// [`escape`](laby_common::internal::escape) ignores these contexts and escapes their children
+    /// like any other text. Elements that are known to be in foreign content at compile time do
+    /// not switch the context at all.
+    pub fn context(&self) -> Option<EscapeContext> {
+        match self.tag.as_str() {
+            _ if self.frag => None,
+            "script" => Some(EscapeContext::Script),
+            "style" => Some(EscapeContext::Style),
+            _ => None,
+        }
+    }
 }
 
 pub struct Node {
     pub element: Element,
+    pub context: EscapeContext,
+    pub static_tail: bool,
+    /// Elements that are currently open, for content model validation.
+    pub ancestors: Vec<Element>,
+    /// Whether content model validation is disabled, if nonzero.
+    pub unchecked: usize,
+    locals: usize,
+    /// Statements that emit compile-time warnings.
+    warnings: Vec<TokenStream>,
     pub decl: NodeDecl,
     pub render: NodeRender,
     pub ctor: NodeCtor,
@@ -70,13 +146,26 @@ pub struct Node {
 
 impl Node {
     pub fn new(element: Element) -> Self {
-        let ident = format_ident!("_{}", element.tag);
+        // custom element names can contain characters that are not valid in identifiers
+        let name: String = element
+            .tag
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        let ident = format_ident!("_{}", name);
         let decl = NodeDecl::new(ident.clone());
         let render = NodeRender::new(ident.clone());
         let ctor = NodeCtor::new(ident.clone());
 
         Self {
             element,
+            context: EscapeContext::Html,
+            static_tail: true,
+            ancestors: Vec::new(),
+            unchecked: 0,
+            locals: 0,
+            warnings: Vec::new(),
             decl,
             render,
             ctor,
@@ -100,19 +189,190 @@ impl Node {
 
         self.render.generics.push(quote!(#ty));
         self.render.generics_bound.push(quote!(#ty: #bounds));
-        self.render.stmts.push(quote!(let #name = self.#name));
+        self.render
+            .generics_bound_send
+            .push(quote!(#ty: #bounds + ::core::marker::Send));
+        self.render
+            .generics_bound_ref
+            .push(quote!(&'laby #ty: #bounds));
+        self.render.fields.push(quote!(let #name = self.#name));
+        self.render.fields_ref.push(quote!(let #name = &self.#name));
+        self.render
+            .hints
+            .push(quote!(::laby::Render::size_hint(&self.#name)));
+        self.render
+            .hints_ref
+            .push(quote!(::laby::Render::size_hint(&&this.#name)));
+
+        self.ctor.fields.push(quote!(#name: #value));
+        self.static_tail = false;
+        name
+    }
+
+    pub fn store_optional(&mut self, value: TokenStream, bounds: TokenStream) -> Ident {
+        let id = self.decl.fields.len() + 1;
+        let name = format_ident!("t{}", id);
+        let ty = format_ident!("T{}", id);
+
+        self.decl.generics.push(quote!(#ty));
+        self.decl
+            .fields
+            .push(quote!(#name: ::core::option::Option<#ty>));
+
+        self.render.generics.push(quote!(#ty));
+        self.render.generics_bound.push(quote!(#ty: #bounds));
+        self.render
+            .generics_bound_send
+            .push(quote!(#ty: #bounds + ::core::marker::Send));
+        self.render
+            .generics_bound_ref
+            .push(quote!(&'laby #ty: #bounds));
+        self.render.fields.push(quote!(let #name = self.#name));
+        self.render.fields_ref.push(quote!(let #name = &self.#name));
+        self.render
+            .hints
+            .push(quote!(::laby::Render::size_hint(&self.#name)));
+        self.render
+            .hints_ref
+            .push(quote!(::laby::Render::size_hint(&&this.#name)));
 
         self.ctor.fields.push(quote!(#name: #value));
+        self.static_tail = false;
         name
     }
 
+    /// Stores a child that implements [`TryRender`](laby_common::TryRender), which makes the node
+    /// implement [`TryRender`](laby_common::TryRender) instead of [`Render`](laby_common::Render).
+    pub fn store_fallible(&mut self, value: TokenStream) -> Ident {
+        let id = self.decl.fields.len() + 1;
+        let name = format_ident!("t{}", id);
+        let ty = format_ident!("T{}", id);
+
+        self.decl.generics.push(quote!(#ty));
+        self.decl.fields.push(quote!(#name: #ty));
+
+        self.render.generics.push(quote!(#ty));
+        self.render
+            .generics_bound
+            .push(quote!(#ty: ::laby::TryRender));
+        self.render.fields.push(quote!(let #name = self.#name));
+        self.render
+            .hints
+            .push(quote!(::laby::TryRender::size_hint(&self.#name)));
+        self.render.fallible.push(ty);
+
+        self.ctor.fields.push(quote!(#name: #value));
+        self.static_tail = false;
+        name
+    }
+
+    /// Stores a child that implements [`AsyncRender`](laby_common::AsyncRender), which makes the
+    /// node implement [`AsyncRender`](laby_common::AsyncRender) instead of
+    /// [`Render`](laby_common::Render).
+    pub fn store_awaited(&mut self, value: TokenStream) -> Ident {
+        let id = self.decl.fields.len() + 1;
+        let name = format_ident!("t{}", id);
+        let ty = format_ident!("T{}", id);
+
+        self.decl.generics.push(quote!(#ty));
+        self.decl.fields.push(quote!(#name: #ty));
+
+        self.render.generics.push(quote!(#ty));
+        self.render
+            .generics_bound
+            .push(quote!(#ty: ::laby::AsyncRender));
+        self.render
+            .generics_bound_send
+            .push(quote!(#ty: ::laby::SendAsyncRender));
+        self.render.fields.push(quote!(let #name = self.#name));
+        self.render.awaited = true;
+
+        self.ctor.fields.push(quote!(#name: #value));
+        self.static_tail = false;
+        name
+    }
+
+    /// Emits a warning at the given span.
+    ///
+    /// Procedural macros cannot emit warnings on stable, so this calls a function named `name`
+    /// that is deprecated with the message as its note, which triggers the `deprecated` lint.
+    pub fn warn(&mut self, name: &str, span: Span, message: String) {
+        let name = Ident::new(name, span);
+        let call = quote_spanned!(span=> #name());
+
+        self.warnings.push(quote!({
+            #[deprecated(note = #message)]
+            fn #name() {}
+            #call;
+        }));
+    }
+
+    /// Returns a unique name for a local variable in the generated render function.
+    pub fn local(&mut self, prefix: &str) -> Ident {
+        self.locals += 1;
+        format_ident!("{}{}", prefix, self.locals)
+    }
+
+    /// Returns `true` if the children of the innermost open element are foreign content, i.e. if
+    /// its nearest foreign ancestor is not `<foreignObject>`.
+    pub fn in_foreign(&self) -> bool {
+        self.ancestors
+            .iter()
+            .rev()
+            .find(|ancestor| ancestor.foreign)
+            .is_some_and(|ancestor| ancestor.tag != "foreignObject")
+    }
+
+    /// Marks the markup written to the buffer as foreign content or not until
+    /// [`Self::restore_foreign`] is called, so that raw text elements nested at runtime are
+    /// escaped correctly.
+    pub fn switch_foreign(&mut self, foreign: bool) -> Ident {
+        let name = self.local("f");
+        self.render
+            .push_let(name.clone(), quote!(buffer.set_foreign(#foreign)));
+        name
+    }
+
+    pub fn restore_foreign(&mut self, name: Ident) {
+        self.render.push_expr(quote!(buffer.set_foreign(#name)));
+    }
+
+    /// Switches the escaping context of the buffer until [`Self::restore_context`] is called.
+    pub fn switch_context(&mut self, context: EscapeContext) -> (Ident, EscapeContext) {
+        let name = self.local("c");
+        let value = match context {
+            EscapeContext::Html => quote!(Html),
+            EscapeContext::Script => quote!(Script),
+            EscapeContext::Style => quote!(Style),
+            EscapeContext::CssValue => quote!(CssValue),
+            EscapeContext::Url => quote!(Url),
+        };
+
+        self.render.push_let(
+            name.clone(),
+            quote!(buffer.set_context(::laby::internal::EscapeContext::#value)),
+        );
+
+        // the buffer ends with the start tag of the raw text element
+        self.static_tail = true;
+        (name, std::mem::replace(&mut self.context, context))
+    }
+
+    pub fn restore_context(&mut self, (name, context): (Ident, EscapeContext)) {
+        self.render.push_expr(quote!(buffer.set_context(#name)));
+        self.context = context;
+        self.static_tail = false;
+    }
+
     pub fn store_concrete(&mut self, value: TokenStream, ty: TokenStream) -> Ident {
         let id = self.decl.fields.len() + 1;
         let name = format_ident!("t{}", id);
 
         self.decl.fields.push(quote!(#name: #ty));
-        self.render.stmts.push(quote!(let #name = self.#name));
+        self.render.fields.push(quote!(let #name = self.#name));
+        self.render.fields_ref.push(quote!(let #name = self.#name));
         self.ctor.fields.push(quote!(#name: #value));
+        self.static_tail = false;
         name
     }
 }
@@ -120,12 +380,17 @@ impl Node {
 impl ToTokens for Node {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let Self {
-            decl, render, ctor, ..
+            warnings,
+            decl,
+            render,
+            ctor,
+            ..
         } = self;
 
         quote!({
             #decl
             #render
+            #(#warnings)*
             #ctor
         })
         .to_tokens(tokens)
@@ -165,12 +430,50 @@ impl ToTokens for NodeDecl {
     }
 }
 
+/// Generates the [`Render`](laby_common::Render) implementations of a node, for the node itself
+/// and for references to it.
+///
+/// The implementation for references is only available if all captured values can be rendered
+/// by reference. Captured values of concrete types are [`Copy`].
+///
+/// The size hint of a node is the length of its static markup plus the size hints of its captured
+/// values. Static markup rendered conditionally is not included.
+///
+/// If any children are fallible, the node implements [`TryRender`](laby_common::TryRender) instead,
+/// returning the error type of the first fallible child. Errors of the other fallible children
+/// are converted using [`From`].
+///
+/// If any children are awaited, the node implements [`AsyncRender`](laby_common::AsyncRender) and
+/// [`SendAsyncRender`](laby_common::SendAsyncRender) instead. Consecutive statements that do not
+/// await are run within a single access to the buffer.
 pub struct NodeRender {
     ident: Ident,
     generics: Punctuated<TokenStream, Comma>,
     generics_bound: Punctuated<TokenStream, Comma>,
-    stmts: Punctuated<TokenStream, Semi>,
+    generics_bound_send: Punctuated<TokenStream, Comma>,
+    generics_bound_ref: Punctuated<TokenStream, Comma>,
+    fields: Punctuated<TokenStream, Semi>,
+    fields_ref: Punctuated<TokenStream, Semi>,
+    hints: Vec<TokenStream>,
+    hints_ref: Vec<TokenStream>,
+    fallible: Vec<Ident>,
+    awaited: bool,
+    stmts: Vec<Stmt>,
     buffer: String,
+    /// Pending static markup when rendered as xhtml, which can differ from `buffer`.
+    buffer_xhtml: String,
+    static_len: usize,
+}
+
+enum Stmt {
+    /// Statement that accesses the buffer.
+    Expr(TokenStream),
+    /// Declaration of a local variable that does not access the buffer.
+    Local(TokenStream),
+    /// Declaration of a local variable initialized by accessing the buffer.
+    Let(Ident, TokenStream),
+    /// Asynchronous child rendered into the buffer.
+    Await(Ident),
 }
 
 impl NodeRender {
@@ -179,28 +482,137 @@ impl NodeRender {
             ident,
             generics: Punctuated::new(),
             generics_bound: Punctuated::new(),
-            stmts: Punctuated::new(),
+            generics_bound_send: Punctuated::new(),
+            generics_bound_ref: Punctuated::new(),
+            fields: Punctuated::new(),
+            fields_ref: Punctuated::new(),
+            hints: Vec::new(),
+            hints_ref: Vec::new(),
+            fallible: Vec::new(),
+            awaited: false,
+            stmts: Vec::new(),
             buffer: String::new(),
+            buffer_xhtml: String::new(),
+            static_len: 0,
         }
     }
 
     pub fn push_str(&mut self, value: impl AsRef<str>) {
         self.buffer.push_str(value.as_ref());
+        self.buffer_xhtml.push_str(value.as_ref());
+    }
+
+    /// Pushes static markup that is rendered differently if the buffer is in xhtml mode.
+    pub fn push_str_xhtml(&mut self, html: impl AsRef<str>, xhtml: impl AsRef<str>) {
+        self.buffer.push_str(html.as_ref());
+        self.buffer_xhtml.push_str(xhtml.as_ref());
+    }
+
+    pub fn pending(&self) -> &str {
+        &self.buffer
+    }
+
+    pub fn is_fallible(&self) -> bool {
+        !self.fallible.is_empty()
+    }
+
+    pub fn is_async(&self) -> bool {
+        self.awaited
     }
 
     pub fn push_expr(&mut self, value: TokenStream) {
         self.flush();
-        self.stmts.push(value);
+        self.stmts.push(Stmt::Expr(value));
+    }
+
+    /// Declares a local variable that does not access the buffer.
+    pub fn push_local(&mut self, value: TokenStream) {
+        self.flush();
+        self.stmts.push(Stmt::Local(value));
+    }
+
+    /// Declares a local variable initialized by an expression that accesses the buffer.
+    pub fn push_let(&mut self, name: Ident, init: TokenStream) {
+        self.flush();
+        self.stmts.push(Stmt::Let(name, init));
+    }
+
+    /// Renders a child stored using [`Node::store_awaited`].
+    pub fn push_await(&mut self, name: Ident) {
+        self.flush();
+        self.stmts.push(Stmt::Await(name));
     }
 
     pub fn flush(&mut self) {
-        if self.buffer.len() != 0 {
-            let literal = Literal::string(&self.buffer);
+        if !self.buffer.is_empty() || !self.buffer_xhtml.is_empty() {
+            let value = xhtml_str(&self.buffer, &self.buffer_xhtml);
+            self.static_len += self.buffer.len();
 
-            self.stmts.push(quote!(buffer.push_str(#literal)));
+            self.stmts.push(Stmt::Expr(quote!(buffer.push_str(#value))));
             self.buffer.clear();
+            self.buffer_xhtml.clear();
         }
     }
+
+    fn sync_stmts(&self) -> TokenStream {
+        let mut tokens = TokenStream::new();
+
+        for stmt in &self.stmts {
+            match stmt {
+                Stmt::Expr(expr) => quote!(#expr;),
+                Stmt::Local(local) => quote!(#local;),
+                Stmt::Let(name, init) => quote!(let #name = #init;),
+                Stmt::Await(_) => unreachable!("awaited child in synchronous node"),
+            }
+            .to_tokens(&mut tokens);
+        }
+
+        tokens
+    }
+
+    fn async_stmts(&self, send: bool) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        let mut exprs = Vec::new();
+
+        for stmt in &self.stmts {
+            if let Stmt::Expr(expr) = stmt {
+                exprs.push(expr);
+                continue;
+            }
+
+            if !exprs.is_empty() {
+                quote!(buffer.with(|buffer| { #(#exprs;)* });).to_tokens(&mut tokens);
+                exprs.clear();
+            }
+
+            match stmt {
+                Stmt::Local(local) => quote!(#local;),
+                Stmt::Let(name, init) => quote!(let #name = buffer.with(|buffer| #init);),
+                Stmt::Await(name) if send => quote!(
+                    ::laby::SendAsyncRender::render_async_send(
+                        #name,
+                        ::core::clone::Clone::clone(&buffer),
+                    )
+                    .await;
+                ),
+                Stmt::Await(name) => quote!(
+                    ::laby::AsyncRender::render_async(
+                        #name,
+                        ::core::clone::Clone::clone(&buffer),
+                    )
+                    .await;
+                ),
+                Stmt::Expr(_) => unreachable!(),
+            }
+            .to_tokens(&mut tokens);
+        }
+
+        if !exprs.is_empty() {
+            quote!(buffer.with(|buffer| { #(#exprs;)* });).to_tokens(&mut tokens);
+        }
+
+        tokens
+    }
 }
 
 impl ToTokens for NodeRender {
@@ -209,17 +621,110 @@ impl ToTokens for NodeRender {
             ident,
             generics,
             generics_bound,
-            stmts,
+            generics_bound_send,
+            generics_bound_ref,
+            fields,
+            fields_ref,
+            hints,
+            hints_ref,
+            fallible,
+            awaited,
             buffer,
+            static_len,
+            ..
         } = self;
 
         assert!(buffer.len() == 0, "render buffer not flushed");
 
+        if *awaited {
+            let stmts = self.async_stmts(false);
+            let stmts_send = self.async_stmts(true);
+
+            return quote!(
+                impl<#generics_bound> ::laby::AsyncRender for #ident<#generics> {
+                    async fn render_async(self, buffer: ::laby::AsyncBuffer) {
+                        #fields;
+                        #stmts
+                    }
+                }
+
+                impl<#generics_bound_send> ::laby::SendAsyncRender for #ident<#generics> {
+                    fn render_async_send(
+                        self,
+                        buffer: ::laby::AsyncBuffer,
+                    ) -> impl ::core::future::Future<Output = ()> + ::core::marker::Send {
+                            async move {
+                            #fields;
+                            #stmts_send
+                        }
+                    }
+                }
+            )
+            .to_tokens(tokens);
+        }
+
+        let stmts = self.sync_stmts();
+
+        if let Some((first, rest)) = fallible.split_first() {
+            let error = quote!(<#first as ::laby::TryRender>::Error);
+
+            return quote!(
+                impl<#generics_bound> ::laby::TryRender for #ident<#generics>
+                where
+                    #(#error: ::core::convert::From<<#rest as ::laby::TryRender>::Error>,)*
+                {
+                    type Error = #error;
+
+                    #[inline]
+                    fn try_render(
+                        self,
+                        buffer: &mut ::laby::internal::Buffer,
+                    ) -> ::core::result::Result<(), Self::Error> {
+                        let context = buffer.context();
+                        let foreign = buffer.foreign();
+                        #fields;
+                        #stmts
+                        ::core::result::Result::Ok(())
+                    }
+
+                    #[inline]
+                    fn size_hint(&self) -> usize {
+                        #static_len #(+ #hints)*
+                    }
+                }
+            )
+            .to_tokens(tokens);
+        }
+
         quote!(
             impl<#generics_bound> ::laby::Render for #ident<#generics> {
                 #[inline]
                 fn render(self, buffer: &mut ::laby::internal::Buffer) {
-                    #stmts;
+                    #fields;
+                    #stmts
+                }
+
+                #[inline]
+                fn size_hint(&self) -> usize {
+                    #static_len #(+ #hints)*
+                }
+            }
+
+            impl<'laby, #generics> ::laby::Render for &'laby #ident<#generics>
+            where
+                #generics_bound_ref
+            {
+                #[inline]
+                fn render(self, buffer: &mut ::laby::internal::Buffer) {
+                    #fields_ref;
+                    #stmts
+                }
+
+                #[inline]
+                #[allow(unused_variables)]
+                fn size_hint(&self) -> usize {
+                    let this: &'laby #ident<#generics> = *self;
+                    #static_len #(+ #hints_ref)*
                 }
             }
         )
@@ -253,3 +758,16 @@ impl ToTokens for NodeCtor {
         .to_tokens(tokens)
     }
 }
+
+/// Returns an expression of a string literal that is chosen at runtime depending on whether the
+/// buffer is in xhtml mode, or the literal itself if both are the same.
+pub fn xhtml_str(html: &str, xhtml: &str) -> TokenStream {
+    let html_lit = Literal::string(html);
+
+    if html == xhtml {
+        quote!(#html_lit)
+    } else {
+        let xhtml = Literal::string(xhtml);
+        quote!(if buffer.xhtml() { #xhtml } else { #html_lit })
+    }
+}